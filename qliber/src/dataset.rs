@@ -1,7 +1,12 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Cursor, Read};
 use std::path::Path;
 
 use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
 use polars::lazy::dsl::{col, lit};
+use polars::lazy::frame::pivot;
 use polars::prelude::*;
 use thiserror::Error;
 
@@ -13,10 +18,89 @@ pub enum DatasetError {
     Load { source: PolarsError },
     #[error("failed to transform market data: {source}")]
     Transform { source: PolarsError },
+    #[error("column `{column}` has dtype {dtype}; expected a date or datetime column")]
+    NotTemporal { column: String, dtype: DataType },
+    #[error("required column `{0}` is missing")]
+    MissingColumn(String),
+    #[error("column `{column}` has dtype {actual}, which cannot be cast to {expected}")]
+    IncompatibleDtype {
+        column: String,
+        expected: DataType,
+        actual: DataType,
+    },
+    #[error("column `{column}` has {nulls} null values and cannot key partitions")]
+    NullKeys { column: String, nulls: usize },
 }
 
 pub type DatasetResult<T> = Result<T, DatasetError>;
 
+/// How [`MarketData::reindex_calendar`] treats rows inserted for missing timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarFill {
+    /// Carry the last observed value of every column forward.
+    Forward,
+    /// Leave the inserted rows null.
+    Null,
+}
+
+/// Which row [`MarketData::dedup_by_time`] keeps among rows sharing a timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeepStrategy {
+    First,
+    Last,
+}
+
+/// Which endpoints [`MarketData::filter_date_range_with_bounds`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntervalBounds {
+    /// `[start, end]`, the behaviour of [`MarketData::filter_date_range`].
+    #[default]
+    Closed,
+    /// `[start, end)`, for chaining non-overlapping chunks.
+    ClosedOpen,
+    /// `(start, end]`.
+    OpenClosed,
+    /// `(start, end)`.
+    Open,
+}
+
+impl IntervalBounds {
+    fn includes_start(self) -> bool {
+        matches!(self, IntervalBounds::Closed | IntervalBounds::ClosedOpen)
+    }
+
+    fn includes_end(self) -> bool {
+        matches!(self, IntervalBounds::Closed | IntervalBounds::OpenClosed)
+    }
+}
+
+/// Parsing options for [`MarketData::from_csv_with_options`].
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Field separator byte, e.g. `b';'`.
+    pub delimiter: u8,
+    pub has_header: bool,
+    /// Rows scanned to infer column dtypes; `None` scans the whole file.
+    pub infer_schema_length: Option<usize>,
+    /// Dtypes forced for the named columns; other columns are still inferred.
+    pub schema: Option<Schema>,
+    /// Try to parse date-like string columns into temporal dtypes. Disable it to keep
+    /// identifiers such as `2024-01-01` or `2024Q1` as strings.
+    pub parse_dates: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            has_header: true,
+            infer_schema_length: Some(2048),
+            schema: None,
+            parse_dates: true,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct MarketData {
     frame: LazyFrame,
@@ -24,17 +108,93 @@ pub struct MarketData {
 
 impl MarketData {
     pub fn from_csv<P: AsRef<Path>>(path: P) -> DatasetResult<Self> {
+        Self::from_csv_with_options(path, &CsvOptions::default())
+    }
+
+    /// Load a CSV file with explicit parsing options.
+    pub fn from_csv_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &CsvOptions,
+    ) -> DatasetResult<Self> {
         let path_ref = path.as_ref();
         let lazy_reader = LazyCsvReader::new(path_ref)
-            .has_header(true)
-            .with_try_parse_dates(true)
-            .with_infer_schema_length(Some(2048));
+            .has_header(options.has_header)
+            .with_separator(options.delimiter)
+            .with_try_parse_dates(options.parse_dates)
+            .with_infer_schema_length(options.infer_schema_length)
+            .with_dtype_overwrite(options.schema.as_ref());
 
         let frame = lazy_reader.finish().map_err(|source| {
             log_event(
                 file!(),
                 "MarketData",
-                "from_csv",
+                "from_csv_with_options",
+                "dataset.load",
+                line!(),
+                &format!("Failed to load {}", path_ref.display()),
+                Some(&source.to_string()),
+                "none",
+                "GET",
+            );
+            DatasetError::Load { source }
+        })?;
+
+        log_event(
+            file!(),
+            "MarketData",
+            "from_csv_with_options",
+            "dataset.load",
+            line!(),
+            &format!(
+                "Loaded dataset from {} with delimiter {:?}",
+                path_ref.display(),
+                char::from(options.delimiter)
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self { frame })
+    }
+
+    /// Load a gzip-compressed CSV file (e.g. `prices.csv.gz`) with the default
+    /// [`CsvOptions`].
+    pub fn from_csv_gz<P: AsRef<Path>>(path: P) -> DatasetResult<Self> {
+        Self::from_csv_gz_with_options(path, &CsvOptions::default())
+    }
+
+    /// Load a gzip-compressed CSV file with explicit parsing options.
+    ///
+    /// The archive is decompressed into memory and parsed eagerly, so the result is
+    /// already materialized behind the lazy frame.
+    pub fn from_csv_gz_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &CsvOptions,
+    ) -> DatasetResult<Self> {
+        let path_ref = path.as_ref();
+        let loaded = File::open(path_ref)
+            .and_then(|file| {
+                let mut decompressed = Vec::new();
+                GzDecoder::new(file).read_to_end(&mut decompressed)?;
+                Ok(decompressed)
+            })
+            .map_err(PolarsError::from)
+            .and_then(|bytes| {
+                CsvReader::new(Cursor::new(bytes))
+                    .has_header(options.has_header)
+                    .with_separator(options.delimiter)
+                    .with_try_parse_dates(options.parse_dates)
+                    .infer_schema(options.infer_schema_length)
+                    .with_dtypes(options.schema.clone().map(Arc::new))
+                    .finish()
+            });
+
+        let frame = loaded.map_err(|source| {
+            log_event(
+                file!(),
+                "MarketData",
+                "from_csv_gz_with_options",
                 "dataset.load",
                 line!(),
                 &format!("Failed to load {}", path_ref.display()),
@@ -48,10 +208,109 @@ impl MarketData {
         log_event(
             file!(),
             "MarketData",
-            "from_csv",
+            "from_csv_gz_with_options",
+            "dataset.load",
+            line!(),
+            &format!(
+                "Loaded {} rows from compressed {}",
+                frame.height(),
+                path_ref.display()
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self {
+            frame: frame.lazy(),
+        })
+    }
+
+    /// Load every CSV file matching the glob `pattern` (e.g. `"data/*.csv"`) and stack them
+    /// vertically in path order. All files must share the same columns.
+    pub fn from_csv_glob(pattern: &str) -> DatasetResult<Self> {
+        let matched = glob::glob(pattern)
+            .map_err(
+                |error| polars_err!(ComputeError: "invalid glob pattern {}: {}", pattern, error),
+            )
+            .and_then(|entries| {
+                entries
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|error| PolarsError::from(std::io::Error::from(error)))
+            })
+            .and_then(|mut paths| {
+                polars_ensure!(!paths.is_empty(), NoData: "no files match {pattern}");
+                paths.sort();
+                Ok(paths)
+            });
+        let paths = matched.map_err(|source| {
+            log_event(
+                file!(),
+                "MarketData",
+                "from_csv_glob",
+                "dataset.load",
+                line!(),
+                &format!("Failed to resolve {pattern}"),
+                Some(&source.to_string()),
+                "none",
+                "GET",
+            );
+            DatasetError::Load { source }
+        })?;
+
+        let frames = paths
+            .iter()
+            .map(|path| Self::from_csv(path).map(|market| market.frame))
+            .collect::<DatasetResult<Vec<_>>>()?;
+        let frame =
+            concat(frames, UnionArgs::default()).map_err(|source| DatasetError::Load { source })?;
+
+        log_event(
+            file!(),
+            "MarketData",
+            "from_csv_glob",
+            "dataset.load",
+            line!(),
+            &format!("Loaded {} files matching {pattern}", paths.len()),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self { frame })
+    }
+
+    /// Load newline-delimited JSON records.
+    ///
+    /// Unlike [`MarketData::from_csv`], timestamps are not auto-parsed and stay as strings;
+    /// cast them (e.g. with `str().strptime`) before date filtering.
+    pub fn from_ndjson<P: AsRef<Path>>(path: P) -> DatasetResult<Self> {
+        let path_ref = path.as_ref();
+        let frame = LazyJsonLineReader::new(path_ref)
+            .with_infer_schema_length(Some(2048))
+            .finish()
+            .map_err(|source| {
+                log_event(
+                    file!(),
+                    "MarketData",
+                    "from_ndjson",
+                    "dataset.load",
+                    line!(),
+                    &format!("Failed to load {}", path_ref.display()),
+                    Some(&source.to_string()),
+                    "none",
+                    "GET",
+                );
+                DatasetError::Load { source }
+            })?;
+
+        log_event(
+            file!(),
+            "MarketData",
+            "from_ndjson",
             "dataset.load",
             line!(),
-            &format!("Loaded dataset from {}", path_ref.display()),
+            &format!("Loaded NDJSON dataset from {}", path_ref.display()),
             None,
             "none",
             "GET",
@@ -60,24 +319,215 @@ impl MarketData {
         Ok(Self { frame })
     }
 
+    /// Wrap an in-memory DataFrame.
+    pub fn from_dataframe(df: DataFrame) -> Self {
+        log_event(
+            file!(),
+            "MarketData",
+            "from_dataframe",
+            "dataset.load",
+            line!(),
+            &format!("Created dataset from DataFrame with shape {:?}", df.shape()),
+            None,
+            "none",
+            "GET",
+        );
+
+        Self { frame: df.lazy() }
+    }
+
+    /// Wrap an existing lazy query plan.
+    pub fn from_lazy(frame: LazyFrame) -> Self {
+        log_event(
+            file!(),
+            "MarketData",
+            "from_lazy",
+            "dataset.load",
+            line!(),
+            "Created dataset from LazyFrame",
+            None,
+            "none",
+            "GET",
+        );
+
+        Self { frame }
+    }
+
     pub fn lazy(&self) -> LazyFrame {
         self.frame.clone()
     }
 
+    /// Resolve the column names and dtypes the lazy plan will produce without collecting.
+    pub fn schema(&self) -> DatasetResult<Schema> {
+        let schema = self
+            .frame
+            .schema()
+            .map_err(|source| DatasetError::Transform { source })?;
+
+        log_event(
+            file!(),
+            "MarketData",
+            "schema",
+            "dataset.inspect",
+            line!(),
+            &format!("Resolved schema with {} columns", schema.len()),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(schema.as_ref().clone())
+    }
+
+    /// Column names of [`MarketData::schema`], in order.
+    pub fn column_names(&self) -> DatasetResult<Vec<String>> {
+        let names = self
+            .schema()?
+            .iter_names()
+            .map(|name| name.to_string())
+            .collect();
+
+        Ok(names)
+    }
+
+    /// Check that every `(name, dtype)` in `required` names an existing column whose dtype
+    /// is `dtype` or castable to it: any numeric dtype satisfies a numeric one, and dates and
+    /// datetimes satisfy any datetime. The first offending column is reported.
+    pub fn validate_schema(&self, required: &[(&str, DataType)]) -> DatasetResult<()> {
+        let schema = self.schema()?;
+        for (name, expected) in required {
+            let failure = match schema.get(name) {
+                None => Some(DatasetError::MissingColumn(name.to_string())),
+                Some(actual) if !castable(actual, expected) => {
+                    Some(DatasetError::IncompatibleDtype {
+                        column: name.to_string(),
+                        expected: expected.clone(),
+                        actual: actual.clone(),
+                    })
+                }
+                Some(_) => None,
+            };
+
+            if let Some(error) = failure {
+                log_event(
+                    file!(),
+                    "MarketData",
+                    "validate_schema",
+                    "dataset.inspect",
+                    line!(),
+                    &format!("Schema validation failed on column {name}"),
+                    Some(&error.to_string()),
+                    "none",
+                    "GET",
+                );
+                return Err(error);
+            }
+        }
+
+        log_event(
+            file!(),
+            "MarketData",
+            "validate_schema",
+            "dataset.inspect",
+            line!(),
+            &format!("Validated {} required columns", required.len()),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(())
+    }
+
+    /// Keep the rows whose `column` lies within `[start, end]`; either bound may be omitted.
+    ///
+    /// The bounds are converted to the column's own dtype, so timezone-aware datetime columns
+    /// are compared on the same UTC instants as naive ones regardless of their time unit.
+    /// A column that is neither a date nor a datetime fails with [`DatasetError::NotTemporal`].
     pub fn filter_date_range(
         &self,
         column: &str,
         start: Option<DateTime<Utc>>,
         end: Option<DateTime<Utc>>,
     ) -> DatasetResult<Self> {
+        self.filter_date_range_with_bounds(column, start, end, IntervalBounds::Closed)
+    }
+
+    /// [`MarketData::filter_date_range`] with each endpoint made inclusive or exclusive
+    /// according to `bounds`.
+    pub fn filter_date_range_with_bounds(
+        &self,
+        column: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        bounds: IntervalBounds,
+    ) -> DatasetResult<Self> {
+        let dtype = self
+            .schema()?
+            .try_get(column)
+            .map_err(|source| DatasetError::Transform { source })?
+            .clone();
+        let bound = |instant: DateTime<Utc>| -> DatasetResult<Expr> {
+            match &dtype {
+                DataType::Date => Ok(lit(instant.naive_utc())),
+                DataType::Datetime(unit, _) => {
+                    let ticks =
+                        match unit {
+                            // Instants outside the ~1677-2262 nanosecond range saturate.
+                            TimeUnit::Nanoseconds => instant.timestamp_nanos_opt().unwrap_or(
+                                if instant.timestamp() < 0 {
+                                    i64::MIN
+                                } else {
+                                    i64::MAX
+                                },
+                            ),
+                            TimeUnit::Microseconds => instant.timestamp_micros(),
+                            TimeUnit::Milliseconds => instant.timestamp_millis(),
+                        };
+                    let typed = Series::new(column, [ticks])
+                        .cast(&dtype)
+                        .map_err(|source| DatasetError::Transform { source })?;
+                    Ok(lit(typed))
+                }
+                other => {
+                    let error = DatasetError::NotTemporal {
+                        column: column.to_string(),
+                        dtype: other.clone(),
+                    };
+                    log_event(
+                        file!(),
+                        "MarketData",
+                        "filter_date_range_with_bounds",
+                        "dataset.filter",
+                        line!(),
+                        &format!("Cannot apply a date filter on column {column}"),
+                        Some(&error.to_string()),
+                        "none",
+                        "GET",
+                    );
+                    Err(error)
+                }
+            }
+        };
+
         let mut filter_expr = col(column).is_not_null();
 
-        if let Some(start) = start.map(|dt| dt.naive_utc()) {
-            filter_expr = filter_expr.and(col(column).gt_eq(lit(start)));
+        if let Some(start) = start {
+            let start = bound(start)?;
+            filter_expr = filter_expr.and(if bounds.includes_start() {
+                col(column).gt_eq(start)
+            } else {
+                col(column).gt(start)
+            });
         }
 
-        if let Some(end) = end.map(|dt| dt.naive_utc()) {
-            filter_expr = filter_expr.and(col(column).lt_eq(lit(end)));
+        if let Some(end) = end {
+            let end = bound(end)?;
+            filter_expr = filter_expr.and(if bounds.includes_end() {
+                col(column).lt_eq(end)
+            } else {
+                col(column).lt(end)
+            });
         }
 
         let filtered = self.frame.clone().filter(filter_expr);
@@ -85,10 +535,31 @@ impl MarketData {
         log_event(
             file!(),
             "MarketData",
-            "filter_date_range",
+            "filter_date_range_with_bounds",
+            "dataset.filter",
+            line!(),
+            &format!("Applied {bounds:?} date filter on {dtype} column {column}"),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self { frame: filtered })
+    }
+
+    /// Lazily keep the rows for which the boolean `expr` holds, e.g.
+    /// `col("close").gt(lit(100.0))`.
+    pub fn filter_expr(&self, expr: Expr) -> DatasetResult<Self> {
+        let description = format!("{expr:?}");
+        let filtered = self.frame.clone().filter(expr);
+
+        log_event(
+            file!(),
+            "MarketData",
+            "filter_expr",
             "dataset.filter",
             line!(),
-            &format!("Applied date filter on column {column}"),
+            &format!("Applied custom filter {description}"),
             None,
             "none",
             "GET",
@@ -97,6 +568,463 @@ impl MarketData {
         Ok(Self { frame: filtered })
     }
 
+    /// Aggregate rows into fixed time windows of length `every` (Polars duration syntax such
+    /// as `"1h"` or `"1d"`), applying `aggregations` such as `col("close").last()` per window.
+    ///
+    /// `time_column` must have a datetime dtype; rows are sorted by it before grouping. The
+    /// grouping stays lazy so it can be executed by the streaming engine.
+    pub fn resample(
+        &self,
+        time_column: &str,
+        every: &str,
+        aggregations: &[Expr],
+    ) -> DatasetResult<Self> {
+        let window = Duration::parse(every);
+        let resampled = self
+            .frame
+            .clone()
+            .sort(time_column, SortOptions::default())
+            .group_by_dynamic(
+                col(time_column),
+                [],
+                DynamicGroupOptions {
+                    every: window,
+                    period: window,
+                    offset: Duration::parse("0ns"),
+                    ..Default::default()
+                },
+            )
+            .agg(aggregations);
+
+        log_event(
+            file!(),
+            "MarketData",
+            "resample",
+            "dataset.transform",
+            line!(),
+            &format!(
+                "Resampled {time_column} into {every} windows with {} aggregations",
+                aggregations.len()
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self { frame: resampled })
+    }
+
+    /// Left-join the data onto a complete datetime range stepping by `every` (Polars duration
+    /// syntax) from the earliest to the latest `time_column` value, filling the inserted rows
+    /// according to `fill`.
+    ///
+    /// Meant for a single instrument; partition multi-symbol data first.
+    pub fn reindex_calendar(
+        &self,
+        time_column: &str,
+        every: &str,
+        fill: CalendarFill,
+    ) -> DatasetResult<Self> {
+        let bounds = self
+            .frame
+            .clone()
+            .select([
+                col(time_column).min().alias("start"),
+                col(time_column).max().alias("end"),
+            ])
+            .collect()
+            .map_err(|source| DatasetError::Transform { source })?;
+
+        let calendar = calendar_range(&bounds, time_column, every).map_err(|source| {
+            log_event(
+                file!(),
+                "MarketData",
+                "reindex_calendar",
+                "dataset.transform",
+                line!(),
+                &format!("Cannot build calendar for {time_column}"),
+                Some(&source.to_string()),
+                "none",
+                "GET",
+            );
+            DatasetError::Transform { source }
+        })?;
+        let calendar_rows = calendar.len();
+
+        let joined = DataFrame::new(vec![calendar])
+            .map_err(|source| DatasetError::Transform { source })?
+            .lazy()
+            .join_builder()
+            .with(self.frame.clone())
+            .left_on([col(time_column)])
+            .right_on([col(time_column)])
+            .how(JoinType::Left)
+            .finish()
+            .sort(time_column, SortOptions::default());
+
+        let reindexed = match fill {
+            CalendarFill::Forward => joined.select([
+                col(time_column),
+                col("*").exclude([time_column]).forward_fill(None),
+            ]),
+            CalendarFill::Null => joined,
+        };
+
+        log_event(
+            file!(),
+            "MarketData",
+            "reindex_calendar",
+            "dataset.transform",
+            line!(),
+            &format!(
+                "Reindexed {time_column} onto {calendar_rows} {every} steps with {fill:?} fill"
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self { frame: reindexed })
+    }
+
+    /// Lazily sort rows by `time_column`.
+    pub fn sort_by_time(&self, time_column: &str, descending: bool) -> DatasetResult<Self> {
+        let sorted = self.frame.clone().sort(
+            time_column,
+            SortOptions {
+                descending,
+                ..Default::default()
+            },
+        );
+
+        log_event(
+            file!(),
+            "MarketData",
+            "sort_by_time",
+            "dataset.transform",
+            line!(),
+            &format!("Sorted by {time_column} (descending: {descending})"),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self { frame: sorted })
+    }
+
+    /// Collect `time_column` and fail unless it is in ascending order.
+    pub fn assert_sorted(&self, time_column: &str) -> DatasetResult<()> {
+        let times = self
+            .frame
+            .clone()
+            .select([col(time_column)])
+            .collect()
+            .map_err(|source| DatasetError::Transform { source })?;
+        let sorted = times
+            .column(time_column)
+            .and_then(|column| column.is_sorted(SortOptions::default()))
+            .map_err(|source| DatasetError::Transform { source })?;
+
+        if !sorted {
+            let source = polars_err!(
+                InvalidOperation: "column {time_column} is not sorted in ascending order"
+            );
+            log_event(
+                file!(),
+                "MarketData",
+                "assert_sorted",
+                "dataset.validate",
+                line!(),
+                &format!("Detected unsorted {time_column}"),
+                Some(&source.to_string()),
+                "none",
+                "GET",
+            );
+            return Err(DatasetError::Transform { source });
+        }
+
+        log_event(
+            file!(),
+            "MarketData",
+            "assert_sorted",
+            "dataset.validate",
+            line!(),
+            &format!("Verified {} rows sorted by {time_column}", times.height()),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(())
+    }
+
+    /// Drop rows whose `time_column` value repeats an earlier row, keeping one per timestamp
+    /// according to `keep`. Surviving rows retain their original order.
+    pub fn dedup_by_time(&self, time_column: &str, keep: KeepStrategy) -> DatasetResult<Self> {
+        let strategy = match keep {
+            KeepStrategy::First => UniqueKeepStrategy::First,
+            KeepStrategy::Last => UniqueKeepStrategy::Last,
+        };
+        let deduplicated = self
+            .frame
+            .clone()
+            .unique_stable(Some(vec![time_column.to_string()]), strategy);
+
+        log_event(
+            file!(),
+            "MarketData",
+            "dedup_by_time",
+            "dataset.transform",
+            line!(),
+            &format!("Deduplicated {time_column} keeping {keep:?}"),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self {
+            frame: deduplicated,
+        })
+    }
+
+    /// Keep only rows whose `symbol_column` value is one of `symbols`.
+    pub fn filter_symbols(&self, symbol_column: &str, symbols: &[&str]) -> DatasetResult<Self> {
+        let allowed = Series::new("symbols", symbols);
+        let filtered = self
+            .frame
+            .clone()
+            .filter(col(symbol_column).is_in(lit(allowed)));
+
+        log_event(
+            file!(),
+            "MarketData",
+            "filter_symbols",
+            "dataset.filter",
+            line!(),
+            &format!(
+                "Filtered {symbol_column} to {} symbols: {}",
+                symbols.len(),
+                symbols.join(", ")
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self { frame: filtered })
+    }
+
+    /// Collect and pivot a long frame into one `value_column` per symbol, indexed by
+    /// `time_column`.
+    ///
+    /// Timestamps keep their first-seen order and symbol columns are sorted by name.
+    /// Missing (time, symbol) combinations are null; duplicates keep the first value.
+    pub fn pivot_wide(
+        &self,
+        time_column: &str,
+        symbol_column: &str,
+        value_column: &str,
+    ) -> DatasetResult<DataFrame> {
+        let frame = self.collect()?;
+        let wide = pivot::pivot_stable(
+            &frame,
+            [value_column],
+            [time_column],
+            [symbol_column],
+            true,
+            None,
+            None,
+        )
+        .map_err(|source| {
+            log_event(
+                file!(),
+                "MarketData",
+                "pivot_wide",
+                "dataset.transform",
+                line!(),
+                &format!("Failed to pivot {value_column} by {symbol_column}"),
+                Some(&source.to_string()),
+                "none",
+                "GET",
+            );
+            DatasetError::Transform { source }
+        })?;
+
+        log_event(
+            file!(),
+            "MarketData",
+            "pivot_wide",
+            "dataset.transform",
+            line!(),
+            &format!(
+                "Pivoted {} rows into {} timestamps and {} symbols",
+                frame.height(),
+                wide.height(),
+                wide.width().saturating_sub(1)
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(wide)
+    }
+
+    /// Split the collected frame into one DataFrame per distinct `symbol_column` value.
+    ///
+    /// Null symbols fail with [`DatasetError::NullKeys`] rather than sharing a key with a
+    /// real instrument.
+    pub fn partition_by_symbol(
+        &self,
+        symbol_column: &str,
+    ) -> DatasetResult<HashMap<String, DataFrame>> {
+        let frame = self.collect()?;
+        let nulls = frame
+            .column(symbol_column)
+            .map_or(0, |column| column.null_count());
+        if nulls > 0 {
+            let error = DatasetError::NullKeys {
+                column: symbol_column.to_string(),
+                nulls,
+            };
+            log_event(
+                file!(),
+                "MarketData",
+                "partition_by_symbol",
+                "dataset.transform",
+                line!(),
+                "Rejected null partition keys",
+                Some(&error.to_string()),
+                "none",
+                "GET",
+            );
+            return Err(error);
+        }
+
+        let partitions = frame
+            .partition_by_stable([symbol_column], true)
+            .map_err(|source| DatasetError::Transform { source })?;
+
+        let mut by_symbol = HashMap::with_capacity(partitions.len());
+        for partition in partitions {
+            let key = partition
+                .column(symbol_column)
+                .and_then(|column| column.cast(&DataType::Utf8))
+                .map_err(|source| DatasetError::Transform { source })?
+                .utf8()
+                .map_err(|source| DatasetError::Transform { source })?
+                .get(0)
+                .expect("null symbols rejected above")
+                .to_string();
+            by_symbol.insert(key, partition);
+        }
+
+        log_event(
+            file!(),
+            "MarketData",
+            "partition_by_symbol",
+            "dataset.transform",
+            line!(),
+            &format!(
+                "Partitioned {} rows into {} symbols by {symbol_column}",
+                frame.height(),
+                by_symbol.len()
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(by_symbol)
+    }
+
+    /// Attach to each row the most recent `other` row at or before its `time_column` value.
+    ///
+    /// Both frames must carry `time_column` with the same dtype; each side is sorted by it
+    /// before joining. Matches further back than `tolerance` are left null.
+    pub fn join_asof(
+        &self,
+        other: &MarketData,
+        time_column: &str,
+        tolerance: Option<chrono::Duration>,
+    ) -> DatasetResult<Self> {
+        let left_schema = self
+            .frame
+            .schema()
+            .map_err(|source| DatasetError::Transform { source })?;
+        let right_schema = other
+            .frame
+            .schema()
+            .map_err(|source| DatasetError::Transform { source })?;
+
+        let checked = match (left_schema.get(time_column), right_schema.get(time_column)) {
+            (Some(left), Some(right)) if left == right => Ok(()),
+            (Some(left), Some(right)) => Err(PolarsError::SchemaMismatch(
+                format!("as-of column {time_column} has dtype {left} on the left but {right} on the right").into(),
+            )),
+            _ => Err(PolarsError::ColumnNotFound(
+                format!("as-of column {time_column} must exist in both frames").into(),
+            )),
+        };
+        if let Err(source) = checked {
+            log_event(
+                file!(),
+                "MarketData",
+                "join_asof",
+                "dataset.transform",
+                line!(),
+                &format!("Cannot as-of join on {time_column}"),
+                Some(&source.to_string()),
+                "none",
+                "GET",
+            );
+            return Err(DatasetError::Transform { source });
+        }
+
+        let tolerance_str = tolerance.map(|window| match window.num_microseconds() {
+            Some(micros) => format!("{micros}us").into(),
+            None => format!("{}s", window.num_seconds()).into(),
+        });
+        let joined = self
+            .frame
+            .clone()
+            .sort(time_column, SortOptions::default())
+            .join_builder()
+            .with(
+                other
+                    .frame
+                    .clone()
+                    .sort(time_column, SortOptions::default()),
+            )
+            .left_on([col(time_column)])
+            .right_on([col(time_column)])
+            .how(JoinType::AsOf(AsOfOptions {
+                strategy: AsofStrategy::Backward,
+                tolerance: None,
+                tolerance_str,
+                left_by: None,
+                right_by: None,
+            }))
+            .finish();
+
+        log_event(
+            file!(),
+            "MarketData",
+            "join_asof",
+            "dataset.transform",
+            line!(),
+            &format!(
+                "As-of joined on {time_column} with tolerance {}",
+                tolerance.map_or_else(|| "none".to_string(), |window| window.to_string())
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self { frame: joined })
+    }
+
     pub fn select_columns(&self, columns: &[&str]) -> DatasetResult<Self> {
         let selection: Vec<Expr> = columns.iter().copied().map(col).collect();
         let selected = self.frame.clone().select(selection);
@@ -116,10 +1044,274 @@ impl MarketData {
         Ok(Self { frame: selected })
     }
 
+    /// Lazily rename columns according to `(from, to)` pairs. Every `from` must exist before
+    /// any rename is applied.
+    pub fn rename(&self, mapping: &[(&str, &str)]) -> DatasetResult<Self> {
+        let schema = self.schema()?;
+        for (existing, _) in mapping {
+            schema
+                .try_get(existing)
+                .map_err(|source| DatasetError::Transform { source })
+                .inspect_err(|error| {
+                    log_event(
+                        file!(),
+                        "MarketData",
+                        "rename",
+                        "dataset.transform",
+                        line!(),
+                        &format!("Cannot rename missing column {existing}"),
+                        Some(&error.to_string()),
+                        "none",
+                        "GET",
+                    );
+                })?;
+        }
+
+        let (existing, new): (Vec<&str>, Vec<&str>) = mapping.iter().copied().unzip();
+        let renamed = self.frame.clone().rename(existing, new);
+
+        log_event(
+            file!(),
+            "MarketData",
+            "rename",
+            "dataset.transform",
+            line!(),
+            &format!(
+                "Renamed columns: {}",
+                mapping
+                    .iter()
+                    .map(|(from, to)| format!("{from} -> {to}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self { frame: renamed })
+    }
+
     pub fn collect(&self) -> DatasetResult<DataFrame> {
         self.frame
             .clone()
             .collect()
             .map_err(|source| DatasetError::Transform { source })
     }
+
+    /// Collect through Polars' streaming engine so filter/select pipelines run in batches
+    /// instead of materializing the whole input first.
+    ///
+    /// Operations the engine cannot stream fall back to the in-memory executor; the
+    /// result matches [`MarketData::collect`].
+    pub fn collect_streaming(&self) -> DatasetResult<DataFrame> {
+        let frame = self
+            .frame
+            .clone()
+            .with_streaming(true)
+            .collect()
+            .map_err(|source| DatasetError::Transform { source })?;
+
+        log_event(
+            file!(),
+            "MarketData",
+            "collect_streaming",
+            "dataset.collect",
+            line!(),
+            &format!(
+                "Collected {} rows with the streaming engine",
+                frame.height()
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(frame)
+    }
+
+    /// Collect only the first `n` rows.
+    pub fn head(&self, n: usize) -> DatasetResult<DataFrame> {
+        let frame = self
+            .frame
+            .clone()
+            .limit(n as IdxSize)
+            .collect()
+            .map_err(|source| DatasetError::Transform { source })?;
+
+        log_event(
+            file!(),
+            "MarketData",
+            "head",
+            "dataset.preview",
+            line!(),
+            &format!("Previewed first {} rows", frame.height()),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(frame)
+    }
+
+    /// Collect only the last `n` rows.
+    pub fn tail(&self, n: usize) -> DatasetResult<DataFrame> {
+        let frame = self
+            .frame
+            .clone()
+            .tail(n as IdxSize)
+            .collect()
+            .map_err(|source| DatasetError::Transform { source })?;
+
+        log_event(
+            file!(),
+            "MarketData",
+            "tail",
+            "dataset.preview",
+            line!(),
+            &format!("Previewed last {} rows", frame.height()),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(frame)
+    }
+
+    /// Collect and draw `n` rows at random without replacement; a fixed `seed` makes the
+    /// draw reproducible. Sampling more rows than exist is an error.
+    pub fn sample(&self, n: usize, seed: Option<u64>) -> DatasetResult<DataFrame> {
+        let frame = self
+            .collect()?
+            .sample_n_literal(n, false, false, seed)
+            .map_err(|source| {
+                log_event(
+                    file!(),
+                    "MarketData",
+                    "sample",
+                    "dataset.preview",
+                    line!(),
+                    &format!("Failed to sample {n} rows"),
+                    Some(&source.to_string()),
+                    "none",
+                    "GET",
+                );
+                DatasetError::Transform { source }
+            })?;
+
+        log_event(
+            file!(),
+            "MarketData",
+            "sample",
+            "dataset.preview",
+            line!(),
+            &format!("Sampled {n} rows with seed {seed:?}"),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(frame)
+    }
+
+    /// Collect the lazy frame and persist it as a Parquet file at `path`.
+    pub fn to_parquet<P: AsRef<Path>>(&self, path: P) -> DatasetResult<()> {
+        let frame = self.collect()?;
+        write_parquet(&frame, path)
+    }
+}
+
+/// Whether a column of dtype `actual` can stand in for `expected` in
+/// [`MarketData::validate_schema`].
+fn castable(actual: &DataType, expected: &DataType) -> bool {
+    actual == expected
+        || (actual.is_numeric() && expected.is_numeric())
+        || matches!(
+            (actual, expected),
+            (
+                DataType::Date | DataType::Datetime(..),
+                DataType::Datetime(..)
+            )
+        )
+}
+
+/// Build the `start`..=`end` datetime range held in `bounds`, typed like the source column.
+fn calendar_range(bounds: &DataFrame, time_column: &str, every: &str) -> PolarsResult<Series> {
+    let start = bounds.column("start")?;
+    let dtype = start.dtype().clone();
+    let DataType::Datetime(unit, _) = dtype else {
+        polars_bail!(SchemaMismatch: "calendar column {time_column} must be a datetime, got {dtype}");
+    };
+
+    let to_naive = |value: i64| {
+        match unit {
+            TimeUnit::Nanoseconds => Some(DateTime::<Utc>::from_timestamp_nanos(value)),
+            TimeUnit::Microseconds => DateTime::<Utc>::from_timestamp_micros(value),
+            TimeUnit::Milliseconds => DateTime::<Utc>::from_timestamp_millis(value),
+        }
+        .map(|timestamp| timestamp.naive_utc())
+    };
+    let endpoint = |name: &str| -> PolarsResult<_> {
+        bounds
+            .column(name)?
+            .datetime()?
+            .get(0)
+            .and_then(to_naive)
+            .ok_or_else(
+                || polars_err!(ComputeError: "calendar column {time_column} has no timestamps"),
+            )
+    };
+
+    let range = polars::time::date_range(
+        time_column,
+        endpoint("start")?,
+        endpoint("end")?,
+        Duration::parse(every),
+        ClosedWindow::Both,
+        unit,
+        None,
+    )?;
+    range.into_series().cast(&dtype)
+}
+
+/// Write a DataFrame to a Parquet file at `path`.
+pub fn write_parquet<P: AsRef<Path>>(frame: &DataFrame, path: P) -> DatasetResult<()> {
+    let path_ref = path.as_ref();
+    let mut frame = frame.clone();
+    let written = File::create(path_ref)
+        .map_err(PolarsError::from)
+        .and_then(|file| ParquetWriter::new(file).finish(&mut frame));
+
+    if let Err(source) = written {
+        log_event(
+            file!(),
+            "MarketData",
+            "write_parquet",
+            "dataset.export",
+            line!(),
+            &format!("Failed to write parquet to {}", path_ref.display()),
+            Some(&source.to_string()),
+            "none",
+            "GET",
+        );
+        return Err(DatasetError::Transform { source });
+    }
+
+    log_event(
+        file!(),
+        "MarketData",
+        "write_parquet",
+        "dataset.export",
+        line!(),
+        &format!(
+            "Wrote {} rows to parquet at {}",
+            frame.height(),
+            path_ref.display()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(())
 }
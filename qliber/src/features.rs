@@ -1,8 +1,69 @@
 use std::collections::VecDeque;
 
 use polars::prelude::*;
+use thiserror::Error;
 
 use crate::logging::log_event;
+use crate::metrics::quantile_sorted;
+
+/// Consistency constant turning a median absolute deviation into a normal-equivalent std.
+const MAD_NORMAL_SCALE: f64 = 1.4826;
+
+#[derive(Debug, Error)]
+pub enum FeatureError {
+    #[error("{name} must be at least {minimum} (got {window})")]
+    InvalidWindow {
+        name: &'static str,
+        window: usize,
+        minimum: usize,
+    },
+    #[error("window {window} exceeds the {rows} available rows")]
+    WindowExceedsRows { window: usize, rows: usize },
+    #[error("invalid feature parameter: {0}")]
+    InvalidParameter(String),
+    #[error("feature requires column `{0}`")]
+    MissingColumn(String),
+    #[error("column `{column}` contains {nulls} nulls")]
+    NullValues { column: String, nulls: usize },
+    #[error("feature received an empty frame")]
+    EmptyFrame,
+    #[error("polars error: {0}")]
+    Polars(#[from] PolarsError),
+}
+
+pub type FeatureResult<T> = Result<T, FeatureError>;
+
+/// How feature functions treat null inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullPolicy {
+    /// Read nulls as 0.0.
+    #[default]
+    Zero,
+    /// Compute over the non-null rows only and write null at the null rows.
+    Skip,
+    /// Fail with an error naming the column.
+    Error,
+    /// Carry the last non-null value forward; leading nulls are skipped.
+    ForwardFill,
+}
+
+/// Fail with [`FeatureError::InvalidWindow`] unless `window >= minimum`.
+fn ensure_window(name: &'static str, window: usize, minimum: usize) -> FeatureResult<()> {
+    if window < minimum {
+        return Err(FeatureError::InvalidWindow {
+            name,
+            window,
+            minimum,
+        });
+    }
+    Ok(())
+}
+
+fn require_column<'a>(frame: &'a DataFrame, name: &str) -> FeatureResult<&'a Series> {
+    frame
+        .column(name)
+        .map_err(|_| FeatureError::MissingColumn(name.to_string()))
+}
 
 fn to_f64_vec(series: &Series) -> PolarsResult<Vec<f64>> {
     let float_series = if series.dtype() != &DataType::Float64 {
@@ -16,27 +77,235 @@ fn to_f64_vec(series: &Series) -> PolarsResult<Vec<f64>> {
 }
 
 /// Compute daily percentage returns from a price column and append them to the DataFrame.
+///
+/// Null prices are read as 0.0; run [`with_forward_fill`] first to carry the last price
+/// across gaps instead, or pick a [`NullPolicy`] via [`with_daily_returns_with_policy`].
 pub fn with_daily_returns(
     frame: &DataFrame,
     price_column: &str,
     output_column: &str,
-) -> PolarsResult<DataFrame> {
-    let prices = to_f64_vec(frame.column(price_column)?)?;
+) -> FeatureResult<DataFrame> {
+    with_daily_returns_with_policy(frame, price_column, output_column, NullPolicy::Zero)
+}
+
+/// [`with_daily_returns`] with explicit handling of null prices.
+pub fn with_daily_returns_with_policy(
+    frame: &DataFrame,
+    price_column: &str,
+    output_column: &str,
+    policy: NullPolicy,
+) -> FeatureResult<DataFrame> {
+    let source = require_column(frame, price_column)?;
+    if source.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let returns = apply_null_policy(source, policy, daily_returns)?;
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, returns))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_daily_returns",
+        "features.returns",
+        line!(),
+        &format!("Computed daily returns for {price_column} -> {output_column} ({policy:?} nulls)"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append daily returns computed separately for each `symbol_column` group, so returns
+/// never span two instruments. Rows stay in their original order.
+///
+/// Each symbol's first row, null prices and zero previous prices yield 0.0, as in
+/// [`with_daily_returns`]. Rows must already be in time order within each symbol.
+pub fn with_daily_returns_grouped(
+    frame: &DataFrame,
+    symbol_column: &str,
+    price_column: &str,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    require_column(frame, symbol_column)?;
+    require_column(frame, price_column)?;
+    let price = col(price_column).cast(DataType::Float64);
+    let previous = price.clone().shift(1).over([col(symbol_column)]);
+    let zero_base = previous
+        .clone()
+        .gt(lit(-f64::EPSILON))
+        .and(previous.clone().lt(lit(f64::EPSILON)));
+    let returns = when(zero_base)
+        .then(lit(0.0))
+        .otherwise(price / previous - lit(1.0))
+        .fill_null(lit(0.0));
+
+    let enriched = frame
+        .clone()
+        .lazy()
+        .with_column(returns.alias(output_column))
+        .collect()?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_daily_returns_grouped",
+        "features.returns",
+        line!(),
+        &format!(
+            "Computed daily returns for {price_column} within {symbol_column} groups -> {output_column}"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append `column` with nulls replaced by the last preceding non-null value.
+///
+/// `limit` caps how many consecutive nulls are filled; leading nulls stay null. This is the
+/// recommended preprocessing before [`with_daily_returns`] and the rolling features, which
+/// otherwise read nulls as 0.0.
+pub fn with_forward_fill(
+    frame: &DataFrame,
+    column: &str,
+    limit: Option<usize>,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    let source = require_column(frame, column)?;
+    let fill_limit = limit.map(|limit| limit.min(IdxSize::MAX as usize) as IdxSize);
+    let mut filled = source.fill_null(FillNullStrategy::Forward(fill_limit))?;
+    filled.rename(output_column);
+    let filled_count = source.null_count() - filled.null_count();
+
+    let mut enriched = frame.clone();
+    enriched.with_column(filled)?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_forward_fill",
+        "features.fill",
+        line!(),
+        &format!(
+            "Forward-filled {filled_count} nulls in {column} (limit {limit:?}) -> {output_column}"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the `periods`-period percentage change `value[t] / value[t - periods] - 1`.
+///
+/// The leading `periods` rows and rows with a zero base value are written as 0.0, as in
+/// [`with_daily_returns`].
+pub fn with_pct_change(
+    frame: &DataFrame,
+    column: &str,
+    periods: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("periods", periods, 1)?;
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let mut changes = vec![0.0; periods.min(values.len())];
+    let mut zero_bases = 0;
+    for window in values.windows(periods + 1) {
+        let base = window[0];
+        let current = window[periods];
+        let pct = if base.abs() < f64::EPSILON {
+            zero_bases += 1;
+            0.0
+        } else {
+            (current / base) - 1.0
+        };
+        changes.push(pct);
+    }
+
+    if zero_bases > 0 {
+        log_event(
+            file!(),
+            "FeatureEngineering",
+            "with_pct_change",
+            "features.returns",
+            line!(),
+            &format!("Zero base value in {zero_bases} rows of {column}; wrote 0.0"),
+            None,
+            "none",
+            "GET",
+        );
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, changes))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_pct_change",
+        "features.returns",
+        line!(),
+        &format!("Computed {periods}-period pct change for {column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Compute log returns `ln(current / prev)` from a price column and append them.
+///
+/// The first row is 0.0. Rows involving a non-positive price are written as 0.0 and logged
+/// instead of producing NaN or -inf.
+pub fn with_log_returns(
+    frame: &DataFrame,
+    price_column: &str,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    let prices = to_f64_vec(require_column(frame, price_column)?)?;
     if prices.is_empty() {
         return Ok(frame.clone());
     }
 
     let mut returns = Vec::with_capacity(prices.len());
+    let mut invalid = 0;
     returns.push(0.0);
     for window in prices.windows(2) {
         let prev = window[0];
         let current = window[1];
-        let pct = if prev.abs() < f64::EPSILON {
-            0.0
+        if prev > 0.0 && current > 0.0 {
+            returns.push((current / prev).ln());
         } else {
-            (current / prev) - 1.0
-        };
-        returns.push(pct);
+            invalid += 1;
+            returns.push(0.0);
+        }
+    }
+
+    if invalid > 0 {
+        log_event(
+            file!(),
+            "FeatureEngineering",
+            "with_log_returns",
+            "features.returns",
+            line!(),
+            &format!("Wrote 0.0 for {invalid} rows with non-positive prices in {price_column}"),
+            None,
+            "none",
+            "GET",
+        );
     }
 
     let mut enriched = frame.clone();
@@ -45,10 +314,113 @@ pub fn with_daily_returns(
     log_event(
         file!(),
         "FeatureEngineering",
-        "with_daily_returns",
+        "with_log_returns",
+        "features.returns",
+        line!(),
+        &format!("Computed log returns for {price_column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the running compounded return `prod(1 + r) - 1` of a per-period returns column.
+///
+/// Non-finite returns are treated as 0.0 for the compounding step and logged.
+pub fn with_cumulative_return(
+    frame: &DataFrame,
+    return_column: &str,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    let returns = to_f64_vec(require_column(frame, return_column)?)?;
+    if returns.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let (growth, non_finite) = compounded_growth(&returns);
+    let cumulative: Vec<f64> = growth.into_iter().map(|factor| factor - 1.0).collect();
+
+    if non_finite > 0 {
+        log_event(
+            file!(),
+            "FeatureEngineering",
+            "with_cumulative_return",
+            "features.returns",
+            line!(),
+            &format!("Treated {non_finite} non-finite returns in {return_column} as 0.0"),
+            None,
+            "none",
+            "GET",
+        );
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, cumulative))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_cumulative_return",
+        "features.returns",
+        line!(),
+        &format!("Computed cumulative return for {return_column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the price path `initial_price * prod(1 + r)` implied by a per-period returns
+/// column, the inverse of [`with_daily_returns`] given the first price.
+///
+/// Non-finite returns are treated as 0.0 for the compounding step and logged.
+pub fn with_reconstructed_price(
+    frame: &DataFrame,
+    return_column: &str,
+    initial_price: f64,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    let returns = to_f64_vec(require_column(frame, return_column)?)?;
+    if returns.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let (growth, non_finite) = compounded_growth(&returns);
+    let prices: Vec<f64> = growth
+        .into_iter()
+        .map(|factor| initial_price * factor)
+        .collect();
+
+    if non_finite > 0 {
+        log_event(
+            file!(),
+            "FeatureEngineering",
+            "with_reconstructed_price",
+            "features.returns",
+            line!(),
+            &format!("Treated {non_finite} non-finite returns in {return_column} as 0.0"),
+            None,
+            "none",
+            "GET",
+        );
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, prices))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_reconstructed_price",
         "features.returns",
         line!(),
-        &format!("Computed daily returns for {price_column} -> {output_column}"),
+        &format!(
+            "Reconstructed prices from {return_column} starting at {initial_price} -> {output_column}"
+        ),
         None,
         "none",
         "GET",
@@ -58,30 +430,33 @@ pub fn with_daily_returns(
 }
 
 /// Append a moving average column computed with a numerically stable rolling window.
+///
+/// Rows before the window fills average the observations seen so far, so a `window`
+/// longer than the frame yields expanding averages; see [`with_moving_average_strict`].
 pub fn with_moving_average(
     frame: &DataFrame,
     price_column: &str,
     window: usize,
     output_column: &str,
-) -> PolarsResult<DataFrame> {
-    assert!(window > 0, "window size must be positive");
-    let prices = to_f64_vec(frame.column(price_column)?)?;
-    if prices.is_empty() {
+) -> FeatureResult<DataFrame> {
+    with_moving_average_with_policy(frame, price_column, window, output_column, NullPolicy::Zero)
+}
+
+/// [`with_moving_average`] with explicit handling of null inputs.
+pub fn with_moving_average_with_policy(
+    frame: &DataFrame,
+    price_column: &str,
+    window: usize,
+    output_column: &str,
+    policy: NullPolicy,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    let source = require_column(frame, price_column)?;
+    if source.is_empty() {
         return Ok(frame.clone());
     }
 
-    let mut averages = Vec::with_capacity(prices.len());
-    let mut sum = 0.0;
-
-    for (idx, value) in prices.iter().enumerate() {
-        sum += value;
-        if idx >= window {
-            sum -= prices[idx - window];
-            averages.push(sum / window as f64);
-        } else {
-            averages.push(sum / (idx + 1) as f64);
-        }
-    }
+    let averages = apply_null_policy(source, policy, |prices| moving_average(prices, window))?;
 
     let mut enriched = frame.clone();
     enriched.with_column(Series::new(output_column, averages))?;
@@ -92,7 +467,9 @@ pub fn with_moving_average(
         "with_moving_average",
         "features.moving_average",
         line!(),
-        &format!("Computed {window}-period moving average for {price_column} -> {output_column}"),
+        &format!(
+            "Computed {window}-period moving average for {price_column} -> {output_column} ({policy:?} nulls)"
+        ),
         None,
         "none",
         "GET",
@@ -101,62 +478,152 @@ pub fn with_moving_average(
     Ok(enriched)
 }
 
-/// Append a rolling z-score normalization column.
-pub fn with_z_score(
+/// [`with_moving_average`] that refuses a `window` longer than the frame instead of
+/// emitting expanding partial averages for every row.
+pub fn with_moving_average_strict(
     frame: &DataFrame,
-    column: &str,
+    price_column: &str,
     window: usize,
     output_column: &str,
-) -> PolarsResult<DataFrame> {
-    assert!(
-        window > 1,
-        "window size must exceed one to compute z-scores"
+) -> FeatureResult<DataFrame> {
+    if frame.height() == 0 {
+        return Err(FeatureError::EmptyFrame);
+    }
+    if window > frame.height() {
+        return Err(FeatureError::WindowExceedsRows {
+            window,
+            rows: frame.height(),
+        });
+    }
+
+    with_moving_average(frame, price_column, window, output_column)
+}
+
+/// Append a moving average for each of `columns`, named `{column}{suffix}`.
+///
+/// Every column is checked before any output is written, so a missing column leaves no
+/// partial result behind.
+pub fn with_moving_average_multi(
+    frame: &DataFrame,
+    columns: &[&str],
+    window: usize,
+    suffix: &str,
+) -> FeatureResult<DataFrame> {
+    for column in columns {
+        require_column(frame, column)?;
+    }
+
+    let mut enriched = frame.clone();
+    for column in columns {
+        enriched = with_moving_average(&enriched, column, window, &format!("{column}{suffix}"))?;
+    }
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_moving_average_multi",
+        "features.moving_average",
+        line!(),
+        &format!(
+            "Computed {window}-period moving averages for {} columns with suffix {suffix}",
+            columns.len()
+        ),
+        None,
+        "none",
+        "GET",
     );
-    let values = to_f64_vec(frame.column(column)?)?;
-    if values.is_empty() {
+
+    Ok(enriched)
+}
+
+/// Append an exponential moving average column using `alpha = 2 / (span + 1)`.
+///
+/// The first value is seeded with the raw price.
+pub fn with_ema(
+    frame: &DataFrame,
+    price_column: &str,
+    span: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("span", span, 1)?;
+    let prices = to_f64_vec(require_column(frame, price_column)?)?;
+    if prices.is_empty() {
         return Ok(frame.clone());
     }
 
-    let mut zscores = Vec::with_capacity(values.len());
-    let mut window_values: VecDeque<f64> = VecDeque::with_capacity(window);
-    let mut sum = 0.0;
-    let mut sum_sq = 0.0;
+    let averages = exponential_moving_average(&prices, span);
 
-    for value in values.iter() {
-        window_values.push_back(*value);
-        sum += value;
-        sum_sq += value * value;
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, averages))?;
 
-        if window_values.len() > window
-            && let Some(old) = window_values.pop_front()
-        {
-            sum -= old;
-            sum_sq -= old * old;
-        }
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_ema",
+        "features.ema",
+        line!(),
+        &format!("Computed {span}-span EMA for {price_column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
 
-        let len = window_values.len() as f64;
-        let mean = sum / len;
-        let variance = (sum_sq / len) - mean * mean;
-        let variance = variance.max(0.0);
-        let std = variance.sqrt();
-        let z = if std > f64::EPSILON {
-            (*value - mean) / std
-        } else {
-            0.0
-        };
-        zscores.push(z);
+    Ok(enriched)
+}
+
+/// Append MACD line, signal line, and histogram columns named `{prefix}_macd`,
+/// `{prefix}_signal`, and `{prefix}_hist`.
+///
+/// The MACD line is the fast EMA minus the slow EMA, the signal line is an EMA of the MACD
+/// line, and the histogram is their difference.
+pub fn with_macd(
+    frame: &DataFrame,
+    price_column: &str,
+    fast: usize,
+    slow: usize,
+    signal: usize,
+    prefix: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("fast span", fast, 1)?;
+    ensure_window("slow span", slow, 1)?;
+    ensure_window("signal span", signal, 1)?;
+    if fast >= slow {
+        return Err(FeatureError::InvalidParameter(format!(
+            "MACD fast span {fast} must be shorter than slow span {slow}"
+        )));
+    }
+
+    let prices = to_f64_vec(require_column(frame, price_column)?)?;
+    if prices.is_empty() {
+        return Ok(frame.clone());
     }
 
+    let fast_ema = exponential_moving_average(&prices, fast);
+    let slow_ema = exponential_moving_average(&prices, slow);
+    let macd: Vec<f64> = fast_ema
+        .iter()
+        .zip(&slow_ema)
+        .map(|(fast, slow)| fast - slow)
+        .collect();
+    let signal_line = exponential_moving_average(&macd, signal);
+    let histogram: Vec<f64> = macd
+        .iter()
+        .zip(&signal_line)
+        .map(|(macd, signal)| macd - signal)
+        .collect();
+
     let mut enriched = frame.clone();
-    enriched.with_column(Series::new(output_column, zscores))?;
+    enriched.with_column(Series::new(&format!("{prefix}_macd"), macd))?;
+    enriched.with_column(Series::new(&format!("{prefix}_signal"), signal_line))?;
+    enriched.with_column(Series::new(&format!("{prefix}_hist"), histogram))?;
 
     log_event(
         file!(),
         "FeatureEngineering",
-        "with_z_score",
-        "features.zscore",
+        "with_macd",
+        "features.macd",
         line!(),
-        &format!("Computed {window}-period z-score for {column} -> {output_column}"),
+        &format!("Computed MACD({fast},{slow},{signal}) for {price_column} -> {prefix}_*"),
         None,
         "none",
         "GET",
@@ -164,3 +631,1585 @@ pub fn with_z_score(
 
     Ok(enriched)
 }
+
+/// Append a Wilder relative strength index column.
+///
+/// The first `window` rows lack a full window of price changes and are filled with a
+/// neutral 50.0. Windows without losses produce 100.0; completely flat windows stay at 50.0.
+pub fn with_rsi(
+    frame: &DataFrame,
+    price_column: &str,
+    window: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    let prices = to_f64_vec(require_column(frame, price_column)?)?;
+    if prices.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let mut rsi = vec![50.0; prices.len()];
+    if prices.len() > window {
+        let changes: Vec<f64> = prices.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        let period = window as f64;
+
+        let mut avg_gain = changes[..window].iter().map(|c| c.max(0.0)).sum::<f64>() / period;
+        let mut avg_loss = changes[..window].iter().map(|c| (-c).max(0.0)).sum::<f64>() / period;
+        rsi[window] = relative_strength_index(avg_gain, avg_loss);
+
+        for (idx, change) in changes.iter().enumerate().skip(window) {
+            avg_gain = (avg_gain * (period - 1.0) + change.max(0.0)) / period;
+            avg_loss = (avg_loss * (period - 1.0) + (-change).max(0.0)) / period;
+            rsi[idx + 1] = relative_strength_index(avg_gain, avg_loss);
+        }
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, rsi))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_rsi",
+        "features.rsi",
+        line!(),
+        &format!("Computed {window}-period RSI for {price_column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append a rolling sample standard deviation column over the trailing window.
+///
+/// Leading rows use the observations available so far; a single observation yields 0.0.
+pub fn with_rolling_std(
+    frame: &DataFrame,
+    column: &str,
+    window: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let mut deviations = Vec::with_capacity(values.len());
+    let mut moments = RollingMoments::new(window);
+
+    for value in values.iter() {
+        moments.push(*value);
+        deviations.push(moments.sample_variance().sqrt());
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, deviations))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_rolling_std",
+        "features.rolling_std",
+        line!(),
+        &format!("Computed {window}-period rolling std for {column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the trailing-window minimum of a column. Leading rows use the observations
+/// available so far.
+pub fn with_rolling_min(
+    frame: &DataFrame,
+    column: &str,
+    window: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let minimums = rolling_extreme(&values, window, |candidate, current| candidate >= current);
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, minimums))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_rolling_min",
+        "features.rolling_extreme",
+        line!(),
+        &format!("Computed {window}-period rolling min for {column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the trailing-window maximum of a column. Leading rows use the observations
+/// available so far.
+pub fn with_rolling_max(
+    frame: &DataFrame,
+    column: &str,
+    window: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let maximums = rolling_extreme(&values, window, |candidate, current| candidate <= current);
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, maximums))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_rolling_max",
+        "features.rolling_extreme",
+        line!(),
+        &format!("Computed {window}-period rolling max for {column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append Bollinger band columns `{prefix}_mid`, `{prefix}_upper`, and `{prefix}_lower`.
+///
+/// The mid band is the rolling mean and the outer bands sit `num_std` rolling standard
+/// deviations away. Leading rows use the observations available so far.
+pub fn with_bollinger_bands(
+    frame: &DataFrame,
+    column: &str,
+    window: usize,
+    num_std: f64,
+    prefix: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let mut mid = Vec::with_capacity(values.len());
+    let mut upper = Vec::with_capacity(values.len());
+    let mut lower = Vec::with_capacity(values.len());
+    let mut moments = RollingMoments::new(window);
+
+    for value in values.iter() {
+        moments.push(*value);
+
+        let mean = moments.mean();
+        let width = num_std * moments.population_variance().sqrt();
+        mid.push(mean);
+        upper.push(mean + width);
+        lower.push(mean - width);
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(&format!("{prefix}_mid"), mid))?;
+    enriched.with_column(Series::new(&format!("{prefix}_upper"), upper))?;
+    enriched.with_column(Series::new(&format!("{prefix}_lower"), lower))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_bollinger_bands",
+        "features.bollinger",
+        line!(),
+        &format!(
+            "Computed {window}-period Bollinger bands ({num_std} std) for {column} -> {prefix}_*"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the trailing-window beta of `asset_column` against `market_column`: the sample
+/// covariance of the two over the market's sample variance.
+///
+/// Leading rows use the observations available so far. Windows where the market does not
+/// move (including the first row) write 0.0 and are counted in the log.
+pub fn with_rolling_beta(
+    frame: &DataFrame,
+    asset_column: &str,
+    market_column: &str,
+    window: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    let asset = to_f64_vec(require_column(frame, asset_column)?)?;
+    let market = to_f64_vec(require_column(frame, market_column)?)?;
+    if asset.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let mut betas = Vec::with_capacity(asset.len());
+    let mut moments = RollingCovariance::new(window);
+    let mut flat_windows = 0;
+    for (&y, &x) in asset.iter().zip(&market) {
+        moments.push(x, y);
+        match moments.beta() {
+            Some(beta) => betas.push(beta),
+            None => {
+                flat_windows += 1;
+                betas.push(0.0);
+            }
+        }
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, betas))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_rolling_beta",
+        "features.rolling_beta",
+        line!(),
+        &format!(
+            "Computed {window}-period rolling beta of {asset_column} on {market_column} -> {output_column} ({flat_windows} zero-variance windows)"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the residual of `y_column` after regressing it on `x_column` over the trailing
+/// window: `y - (alpha + beta * x)` at each row, with `alpha` and `beta` fitted by least
+/// squares on the window ending there.
+///
+/// Leading rows fit on the observations available so far. Windows where `x` does not move
+/// (including the first row) pass the raw `y` through and are counted in the log.
+pub fn with_residual(
+    frame: &DataFrame,
+    y_column: &str,
+    x_column: &str,
+    window: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    let y_values = to_f64_vec(require_column(frame, y_column)?)?;
+    let x_values = to_f64_vec(require_column(frame, x_column)?)?;
+    if y_values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let mut residuals = Vec::with_capacity(y_values.len());
+    let mut moments = RollingCovariance::new(window);
+    let mut degenerate_windows = 0;
+    for (&y, &x) in y_values.iter().zip(&x_values) {
+        moments.push(x, y);
+        match moments.fit() {
+            Some((alpha, beta)) => residuals.push(y - (alpha + beta * x)),
+            None => {
+                degenerate_windows += 1;
+                residuals.push(y);
+            }
+        }
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, residuals))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_residual",
+        "features.residual",
+        line!(),
+        &format!(
+            "Computed {window}-period residual of {y_column} on {x_column} -> {output_column} ({degenerate_windows} degenerate windows)"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append a cross-sectional rank of `value_column` normalized into `(0, 1]` within each
+/// `group_column` bucket (e.g. per timestamp).
+///
+/// Ties receive their average rank and the highest value in a group maps to 1.0. The ranking
+/// runs as a Polars window expression rather than a manual loop.
+pub fn with_cross_sectional_rank(
+    frame: &DataFrame,
+    value_column: &str,
+    group_column: &str,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    require_column(frame, value_column)?;
+    require_column(frame, group_column)?;
+    let rank = col(value_column)
+        .rank(
+            RankOptions {
+                method: RankMethod::Average,
+                descending: false,
+            },
+            None,
+        )
+        .cast(DataType::Float64);
+    let group_size = col(value_column)
+        .is_not_null()
+        .sum()
+        .cast(DataType::Float64);
+
+    let enriched = frame
+        .clone()
+        .lazy()
+        .with_column(
+            (rank / group_size)
+                .over([col(group_column)])
+                .alias(output_column),
+        )
+        .collect()?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_cross_sectional_rank",
+        "features.cross_sectional",
+        line!(),
+        &format!(
+            "Computed cross-sectional rank of {value_column} grouped by {group_column} -> {output_column}"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append `value_column` standardized within each `group_column` bucket (e.g. per
+/// timestamp): the group mean is subtracted and the result divided by the group's sample std.
+///
+/// Groups with a single member or no dispersion write 0.0. Like [`with_cross_sectional_rank`],
+/// the statistics run as Polars window expressions.
+pub fn with_cross_sectional_z_score(
+    frame: &DataFrame,
+    value_column: &str,
+    group_column: &str,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    require_column(frame, value_column)?;
+    require_column(frame, group_column)?;
+    let mean = col(value_column).mean().over([col(group_column)]);
+    let std = col(value_column).std(1).over([col(group_column)]);
+    let z_score = when(std.clone().gt(lit(0.0)))
+        .then((col(value_column) - mean) / std)
+        .otherwise(lit(0.0));
+
+    let enriched = frame
+        .clone()
+        .lazy()
+        .with_column(z_score.alias(output_column))
+        .collect()?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_cross_sectional_z_score",
+        "features.cross_sectional",
+        line!(),
+        &format!(
+            "Computed cross-sectional z-score of {value_column} grouped by {group_column} -> {output_column}"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append a winsorized copy of `column`, clipped to its `lower_quantile` and
+/// `upper_quantile` values.
+///
+/// Quantiles are estimated over the finite values of the whole column with linear
+/// interpolation; nulls and non-finite values are passed through unchanged, so a column
+/// without finite values is copied as is.
+pub fn with_winsorized(
+    frame: &DataFrame,
+    column: &str,
+    lower_quantile: f64,
+    upper_quantile: f64,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    if !((0.0..=1.0).contains(&lower_quantile)
+        && (0.0..=1.0).contains(&upper_quantile)
+        && lower_quantile < upper_quantile)
+    {
+        return Err(FeatureError::InvalidParameter(format!(
+            "winsorization quantiles must satisfy 0 <= lower < upper <= 1 (got {lower_quantile} and {upper_quantile})"
+        )));
+    }
+
+    let values: Vec<Option<f64>> = require_column(frame, column)?
+        .cast(&DataType::Float64)?
+        .f64()?
+        .into_iter()
+        .collect();
+    let mut finite: Vec<f64> = values
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|v| v.is_finite())
+        .collect();
+    finite.sort_by(f64::total_cmp);
+
+    let bounds = (!finite.is_empty()).then(|| {
+        (
+            quantile_sorted(&finite, lower_quantile),
+            quantile_sorted(&finite, upper_quantile),
+        )
+    });
+    let clipped: Vec<Option<f64>> = values
+        .iter()
+        .map(|value| match (value, bounds) {
+            (Some(v), Some((lower, upper))) if v.is_finite() => Some(v.clamp(lower, upper)),
+            _ => *value,
+        })
+        .collect();
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, clipped))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_winsorized",
+        "features.winsorize",
+        line!(),
+        &match bounds {
+            Some((lower, upper)) => format!(
+                "Winsorized {column} to [{lower:.6}, {upper:.6}] (quantiles {lower_quantile}-{upper_quantile}) -> {output_column}"
+            ),
+            None => {
+                format!("Column {column} has no finite values; copied unchanged to {output_column}")
+            }
+        },
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append `column` shifted down by `periods` rows, leaving the vacated leading rows null.
+///
+/// Negative `periods` shift upward (a lead), nulling the trailing rows instead.
+pub fn with_lag(
+    frame: &DataFrame,
+    column: &str,
+    periods: i64,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    let mut lagged = require_column(frame, column)?.shift(periods);
+    lagged.rename(output_column);
+
+    let mut enriched = frame.clone();
+    enriched.with_column(lagged)?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_lag",
+        "features.lag",
+        line!(),
+        &format!("Shifted {column} by {periods} periods -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Like [`with_lag`], but fills the vacated rows with `fill_value` and emits an f64 column.
+pub fn with_lag_filled(
+    frame: &DataFrame,
+    column: &str,
+    periods: i64,
+    fill_value: f64,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    let lagged = require_column(frame, column)?
+        .cast(&DataType::Float64)?
+        .shift(periods);
+    let filled: Vec<f64> = lagged
+        .f64()?
+        .into_iter()
+        .map(|value| value.unwrap_or(fill_value))
+        .collect();
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, filled))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_lag_filled",
+        "features.lag",
+        line!(),
+        &format!(
+            "Shifted {column} by {periods} periods filling with {fill_value} -> {output_column}"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append a rolling z-score normalization column.
+pub fn with_z_score(
+    frame: &DataFrame,
+    column: &str,
+    window: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    with_z_score_with_policy(frame, column, window, output_column, NullPolicy::Zero)
+}
+
+/// [`with_z_score`] with explicit handling of null inputs.
+pub fn with_z_score_with_policy(
+    frame: &DataFrame,
+    column: &str,
+    window: usize,
+    output_column: &str,
+    policy: NullPolicy,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 2)?;
+    let source = require_column(frame, column)?;
+    if source.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let zscores = apply_null_policy(source, policy, |values| rolling_z_scores(values, window))?;
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, zscores))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_z_score",
+        "features.zscore",
+        line!(),
+        &format!(
+            "Computed {window}-period z-score for {column} -> {output_column} ({policy:?} nulls)"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Lazy counterpart of [`with_daily_returns`]: `price / previous - 1`.
+///
+/// The first row and rows following a zero price are 0.0, as in the eager version. Nulls
+/// are not read as 0.0 here: a null price and the row after it both yield 0.0.
+pub fn daily_returns_expr(column: &str) -> Expr {
+    let prices = col(column).cast(DataType::Float64);
+    let previous = prices.clone().shift(1);
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "daily_returns_expr",
+        "features.returns",
+        line!(),
+        &format!("Built lazy daily returns expression for {column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    when(previous.clone().abs().lt(lit(f64::EPSILON)))
+        .then(lit(0.0))
+        .otherwise(prices / previous - lit(1.0))
+        .fill_null(lit(0.0))
+}
+
+/// Lazy counterpart of [`with_moving_average`], including the expanding averages over
+/// leading partial windows. Nulls are skipped by the engine rather than read as 0.0.
+pub fn moving_average_expr(column: &str, window: usize) -> FeatureResult<Expr> {
+    ensure_window("window", window, 1)?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "moving_average_expr",
+        "features.moving_average",
+        line!(),
+        &format!("Built lazy {window}-period moving average expression for {column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(col(column)
+        .cast(DataType::Float64)
+        .rolling_mean(rolling_options(window)))
+}
+
+/// Lazy counterpart of [`with_z_score`] for composing into a `LazyFrame`.
+///
+/// The rolling mean and deviation run inside the Polars query engine, so the work stays
+/// lazy and is parallelized alongside the rest of the plan. Like the eager version it
+/// uses the population deviation over partial leading windows and yields 0.0 for flat
+/// windows; nulls are skipped by the engine rather than read as 0.0. The result keeps the
+/// name of `column`, so alias it as needed.
+pub fn z_score_expr(column: &str, window: usize) -> FeatureResult<Expr> {
+    ensure_window("window", window, 2)?;
+    let values = col(column).cast(DataType::Float64);
+    let observed = values
+        .clone()
+        .is_not_null()
+        .cast(DataType::Float64)
+        .rolling_sum(rolling_options(window));
+    let mean = values.clone().rolling_mean(rolling_options(window));
+    let std = values.clone().rolling_std(rolling_options(window))
+        * ((observed.clone() - lit(1.0)) / observed).sqrt();
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "z_score_expr",
+        "features.zscore",
+        line!(),
+        &format!("Built lazy {window}-period z-score expression for {column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(when(std.clone().gt(lit(f64::EPSILON)))
+        .then((values - mean) / std)
+        .otherwise(lit(0.0)))
+}
+
+/// Append a rolling z-score that centres on the window median and scales by the median
+/// absolute deviation (times 1.4826, so it matches the std for normal data).
+///
+/// Leading rows use the observations available so far. Rows whose window has zero MAD
+/// are written as 0.0.
+pub fn with_robust_z_score(
+    frame: &DataFrame,
+    column: &str,
+    window: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 2)?;
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let mut zscores = Vec::with_capacity(values.len());
+    let mut flat_windows = 0;
+
+    for (idx, value) in values.iter().enumerate() {
+        let mut observations = values[(idx + 1).saturating_sub(window)..=idx].to_vec();
+        observations.sort_by(f64::total_cmp);
+        let median = quantile_sorted(&observations, 0.5);
+
+        let mut deviations: Vec<f64> = observations.iter().map(|v| (v - median).abs()).collect();
+        deviations.sort_by(f64::total_cmp);
+        let scale = MAD_NORMAL_SCALE * quantile_sorted(&deviations, 0.5);
+
+        if scale > f64::EPSILON {
+            zscores.push((value - median) / scale);
+        } else {
+            flat_windows += 1;
+            zscores.push(0.0);
+        }
+    }
+
+    if flat_windows > 0 {
+        log_event(
+            file!(),
+            "FeatureEngineering",
+            "with_robust_z_score",
+            "features.zscore",
+            line!(),
+            &format!("Zero MAD in {flat_windows} windows of {column}; wrote 0.0"),
+            None,
+            "none",
+            "GET",
+        );
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, zscores))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_robust_z_score",
+        "features.zscore",
+        line!(),
+        &format!("Computed {window}-period robust z-score for {column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the trailing-window volume-weighted average price,
+/// `sum(price * volume) / sum(volume)`.
+///
+/// Leading rows use the observations available so far. Windows with zero total volume
+/// produce null rather than NaN.
+pub fn with_vwap(
+    frame: &DataFrame,
+    price_column: &str,
+    volume_column: &str,
+    window: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    let prices = to_f64_vec(require_column(frame, price_column)?)?;
+    let volumes = to_f64_vec(require_column(frame, volume_column)?)?;
+    if prices.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let mut vwap: Vec<Option<f64>> = Vec::with_capacity(prices.len());
+    let mut trailing: VecDeque<(f64, f64)> = VecDeque::with_capacity(window);
+    let mut notional = 0.0;
+    let mut volume_sum = 0.0;
+    let mut empty_windows = 0;
+
+    for (price, volume) in prices.iter().zip(&volumes) {
+        trailing.push_back((price * volume, *volume));
+        notional += price * volume;
+        volume_sum += volume;
+
+        if trailing.len() > window
+            && let Some((old_notional, old_volume)) = trailing.pop_front()
+        {
+            notional -= old_notional;
+            volume_sum -= old_volume;
+        }
+
+        if volume_sum.abs() > f64::EPSILON {
+            vwap.push(Some(notional / volume_sum));
+        } else {
+            empty_windows += 1;
+            vwap.push(None);
+        }
+    }
+
+    if empty_windows > 0 {
+        log_event(
+            file!(),
+            "FeatureEngineering",
+            "with_vwap",
+            "features.vwap",
+            line!(),
+            &format!("Zero {volume_column} in {empty_windows} windows; wrote null"),
+            None,
+            "none",
+            "GET",
+        );
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, vwap))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_vwap",
+        "features.vwap",
+        line!(),
+        &format!(
+            "Computed {window}-period VWAP from {price_column} and {volume_column} -> {output_column}"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the column rescaled into `[0, 1]` by its global minimum and maximum.
+///
+/// A constant column has no range and is written as 0.5.
+pub fn with_min_max_scaled(
+    frame: &DataFrame,
+    column: &str,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let (minimum, maximum) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), value| {
+            (lo.min(*value), hi.max(*value))
+        });
+    let mut flat_rows = 0;
+    let scaled: Vec<f64> = values
+        .iter()
+        .map(|value| min_max_scale(*value, minimum, maximum, &mut flat_rows))
+        .collect();
+
+    if flat_rows > 0 {
+        log_event(
+            file!(),
+            "FeatureEngineering",
+            "with_min_max_scaled",
+            "features.scale",
+            line!(),
+            &format!("{column} is constant; wrote 0.5"),
+            None,
+            "none",
+            "GET",
+        );
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, scaled))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_min_max_scaled",
+        "features.scale",
+        line!(),
+        &format!("Min-max scaled {column} into [{minimum}, {maximum}] -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the column rescaled into `[0, 1]` by the minimum and maximum of the trailing
+/// window. Leading rows use the observations available so far; flat windows yield 0.5.
+pub fn with_rolling_min_max_scaled(
+    frame: &DataFrame,
+    column: &str,
+    window: usize,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let minimums = rolling_extreme(&values, window, |candidate, current| candidate >= current);
+    let maximums = rolling_extreme(&values, window, |candidate, current| candidate <= current);
+    let mut flat_rows = 0;
+    let scaled: Vec<f64> = values
+        .iter()
+        .zip(minimums.iter().zip(&maximums))
+        .map(|(value, (minimum, maximum))| {
+            min_max_scale(*value, *minimum, *maximum, &mut flat_rows)
+        })
+        .collect();
+
+    if flat_rows > 0 {
+        log_event(
+            file!(),
+            "FeatureEngineering",
+            "with_rolling_min_max_scaled",
+            "features.scale",
+            line!(),
+            &format!("{column} is flat in {flat_rows} windows; wrote 0.5"),
+            None,
+            "none",
+            "GET",
+        );
+    }
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, scaled))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_rolling_min_max_scaled",
+        "features.scale",
+        line!(),
+        &format!("Computed {window}-period min-max scaling for {column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the linearly interpolated `quantile` of the trailing window at each row.
+///
+/// Leading rows use the observations available so far.
+pub fn with_rolling_quantile(
+    frame: &DataFrame,
+    column: &str,
+    window: usize,
+    quantile: f64,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    ensure_window("window", window, 1)?;
+    if !(0.0..=1.0).contains(&quantile) {
+        return Err(FeatureError::InvalidParameter(format!(
+            "rolling quantile must lie in [0, 1] (got {quantile})"
+        )));
+    }
+
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let quantiles: Vec<f64> = (0..values.len())
+        .map(|idx| {
+            let mut observations = values[(idx + 1).saturating_sub(window)..=idx].to_vec();
+            observations.sort_by(f64::total_cmp);
+            quantile_sorted(&observations, quantile)
+        })
+        .collect();
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, quantiles))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_rolling_quantile",
+        "features.rolling_quantile",
+        line!(),
+        &format!(
+            "Computed {window}-period rolling {quantile} quantile for {column} -> {output_column}"
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append `f` applied to the trailing window of `column` at each row.
+///
+/// Leading rows pass the observations available so far, so `f` must accept slices shorter
+/// than `window`. Nulls are read as 0.0.
+pub fn with_rolling_apply<F>(
+    frame: &DataFrame,
+    column: &str,
+    window: usize,
+    output_column: &str,
+    f: F,
+) -> FeatureResult<DataFrame>
+where
+    F: Fn(&[f64]) -> f64,
+{
+    ensure_window("window", window, 1)?;
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let applied: Vec<f64> = (0..values.len())
+        .map(|idx| f(&values[(idx + 1).saturating_sub(window)..=idx]))
+        .collect();
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, applied))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_rolling_apply",
+        "features.rolling_apply",
+        line!(),
+        &format!("Applied custom {window}-period rolling function to {column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the mean of all observations up to and including each row.
+pub fn with_expanding_mean(
+    frame: &DataFrame,
+    column: &str,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let means: Vec<f64> = expanding_moments(&values)
+        .into_iter()
+        .map(|(mean, _)| mean)
+        .collect();
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, means))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_expanding_mean",
+        "features.expanding",
+        line!(),
+        &format!("Computed expanding mean for {column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the sample standard deviation of all observations up to and including each row.
+/// The first row yields 0.0.
+pub fn with_expanding_std(
+    frame: &DataFrame,
+    column: &str,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    let values = to_f64_vec(require_column(frame, column)?)?;
+    if values.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let deviations: Vec<f64> = expanding_moments(&values)
+        .into_iter()
+        .map(|(_, variance)| variance.sqrt())
+        .collect();
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, deviations))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_expanding_std",
+        "features.expanding",
+        line!(),
+        &format!("Computed expanding std for {column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+/// Append the fraction of all observations up to and including each row that are less than
+/// or equal to that row's value, so the latest value is ranked against its own history only.
+///
+/// Because the row itself counts, the first row is always 1.0 and a new running high ranks
+/// 1.0, so a monotonically increasing series stays at 1.0 throughout; lower values pull the
+/// rank towards 0. Null rows rank null and are left out of later histories.
+pub fn with_expanding_percentile_rank(
+    frame: &DataFrame,
+    column: &str,
+    output_column: &str,
+) -> FeatureResult<DataFrame> {
+    let series = require_column(frame, column)?;
+    if series.is_empty() {
+        return Ok(frame.clone());
+    }
+
+    let ranks = apply_null_policy(series, NullPolicy::Skip, expanding_percentile_ranks)?;
+
+    let mut enriched = frame.clone();
+    enriched.with_column(Series::new(output_column, ranks))?;
+
+    log_event(
+        file!(),
+        "FeatureEngineering",
+        "with_expanding_percentile_rank",
+        "features.expanding",
+        line!(),
+        &format!("Computed expanding percentile rank for {column} -> {output_column}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(enriched)
+}
+
+type FeatureStep = Box<dyn Fn(&DataFrame) -> FeatureResult<DataFrame> + Send + Sync>;
+
+/// Ordered chain of feature transforms applied to a frame in one call.
+///
+/// Each step receives the output of the previous one; the first failing step stops the
+/// chain and its error is returned.
+#[derive(Default)]
+pub struct FeaturePipeline {
+    steps: Vec<FeatureStep>,
+}
+
+impl FeaturePipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an arbitrary transform.
+    pub fn step<F>(mut self, transform: F) -> Self
+    where
+        F: Fn(&DataFrame) -> FeatureResult<DataFrame> + Send + Sync + 'static,
+    {
+        self.steps.push(Box::new(transform));
+        self
+    }
+
+    pub fn daily_returns(self, price_column: &str, output_column: &str) -> Self {
+        let (price_column, output_column) = (price_column.to_string(), output_column.to_string());
+        self.step(move |frame| with_daily_returns(frame, &price_column, &output_column))
+    }
+
+    pub fn moving_average(self, column: &str, window: usize, output_column: &str) -> Self {
+        let (column, output_column) = (column.to_string(), output_column.to_string());
+        self.step(move |frame| with_moving_average(frame, &column, window, &output_column))
+    }
+
+    pub fn z_score(self, column: &str, window: usize, output_column: &str) -> Self {
+        let (column, output_column) = (column.to_string(), output_column.to_string());
+        self.step(move |frame| with_z_score(frame, &column, window, &output_column))
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Run every step in order starting from `frame`.
+    pub fn apply(&self, frame: &DataFrame) -> FeatureResult<DataFrame> {
+        let mut current = frame.clone();
+        for (idx, transform) in self.steps.iter().enumerate() {
+            current = transform(&current).inspect_err(|error| {
+                log_event(
+                    file!(),
+                    "FeaturePipeline",
+                    "apply",
+                    "features.pipeline",
+                    line!(),
+                    &format!("Step {} of {} failed", idx + 1, self.steps.len()),
+                    Some(&error.to_string()),
+                    "none",
+                    "GET",
+                );
+            })?;
+        }
+
+        log_event(
+            file!(),
+            "FeaturePipeline",
+            "apply",
+            "features.pipeline",
+            line!(),
+            &format!("Applied {} feature steps", self.steps.len()),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(current)
+    }
+}
+
+/// Trailing-window sum and sum of squares maintained incrementally for O(n) statistics.
+struct RollingMoments {
+    window: usize,
+    values: VecDeque<f64>,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl RollingMoments {
+    fn new(window: usize) -> Self {
+        Self {
+            window,
+            values: VecDeque::with_capacity(window),
+            sum: 0.0,
+            sum_sq: 0.0,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        self.values.push_back(value);
+        self.sum += value;
+        self.sum_sq += value * value;
+
+        if self.values.len() > self.window
+            && let Some(old) = self.values.pop_front()
+        {
+            self.sum -= old;
+            self.sum_sq -= old * old;
+        }
+    }
+
+    fn mean(&self) -> f64 {
+        self.sum / self.values.len() as f64
+    }
+
+    fn population_variance(&self) -> f64 {
+        let len = self.values.len() as f64;
+        let mean = self.sum / len;
+        ((self.sum_sq / len) - mean * mean).max(0.0)
+    }
+
+    fn sample_variance(&self) -> f64 {
+        let len = self.values.len();
+        if len < 2 {
+            return 0.0;
+        }
+        let len = len as f64;
+        ((self.sum_sq - self.sum * self.sum / len) / (len - 1.0)).max(0.0)
+    }
+}
+
+/// Trailing-window co-moments of an `(x, y)` pair maintained incrementally, like
+/// [`RollingMoments`].
+struct RollingCovariance {
+    window: usize,
+    pairs: VecDeque<(f64, f64)>,
+    sum_x: f64,
+    sum_y: f64,
+    sum_xx: f64,
+    sum_xy: f64,
+}
+
+impl RollingCovariance {
+    fn new(window: usize) -> Self {
+        Self {
+            window,
+            pairs: VecDeque::with_capacity(window),
+            sum_x: 0.0,
+            sum_y: 0.0,
+            sum_xx: 0.0,
+            sum_xy: 0.0,
+        }
+    }
+
+    fn push(&mut self, x: f64, y: f64) {
+        self.pairs.push_back((x, y));
+        self.sum_x += x;
+        self.sum_y += y;
+        self.sum_xx += x * x;
+        self.sum_xy += x * y;
+
+        if self.pairs.len() > self.window
+            && let Some((old_x, old_y)) = self.pairs.pop_front()
+        {
+            self.sum_x -= old_x;
+            self.sum_y -= old_y;
+            self.sum_xx -= old_x * old_x;
+            self.sum_xy -= old_x * old_y;
+        }
+    }
+
+    /// Slope of the least-squares fit of `y` on `x`, or `None` when `x` has no variance.
+    fn beta(&self) -> Option<f64> {
+        let len = self.pairs.len() as f64;
+        let variance_x = self.sum_xx - self.sum_x * self.sum_x / len;
+        let covariance = self.sum_xy - self.sum_x * self.sum_y / len;
+        (variance_x > f64::EPSILON * self.sum_xx.max(1.0)).then(|| covariance / variance_x)
+    }
+
+    /// Intercept and slope of the least-squares fit of `y` on `x`.
+    fn fit(&self) -> Option<(f64, f64)> {
+        let len = self.pairs.len() as f64;
+        let beta = self.beta()?;
+        Some((self.sum_y / len - beta * self.sum_x / len, beta))
+    }
+}
+
+/// Running mean and sample variance of every prefix of `values` (Welford's update).
+fn expanding_moments(values: &[f64]) -> Vec<(f64, f64)> {
+    let mut moments = Vec::with_capacity(values.len());
+    let mut mean = 0.0;
+    let mut squared_deviations = 0.0;
+
+    for (idx, value) in values.iter().enumerate() {
+        let count = (idx + 1) as f64;
+        let delta = value - mean;
+        mean += delta / count;
+        squared_deviations += delta * (value - mean);
+
+        let variance = if idx > 0 {
+            (squared_deviations / (count - 1.0)).max(0.0)
+        } else {
+            0.0
+        };
+        moments.push((mean, variance));
+    }
+
+    moments
+}
+
+/// Expanding `<=` percentile rank of each value, counted with a Fenwick tree over the
+/// sorted distinct values so long histories stay `O(n log n)`.
+fn expanding_percentile_ranks(values: &[f64]) -> Vec<f64> {
+    let mut distinct = values.to_vec();
+    distinct.sort_by(f64::total_cmp);
+    distinct.dedup_by(|a, b| a.total_cmp(b).is_eq());
+
+    let mut tree = vec![0usize; distinct.len() + 1];
+    values
+        .iter()
+        .enumerate()
+        .map(|(seen, value)| {
+            let slot = distinct.partition_point(|probe| probe.total_cmp(value).is_lt()) + 1;
+            let mut index = slot;
+            while index < tree.len() {
+                tree[index] += 1;
+                index += index & index.wrapping_neg();
+            }
+
+            let mut at_or_below = 0;
+            let mut index = slot;
+            while index > 0 {
+                at_or_below += tree[index];
+                index -= index & index.wrapping_neg();
+            }
+            at_or_below as f64 / (seen + 1) as f64
+        })
+        .collect()
+}
+
+/// Run `compute` over `series` after resolving its nulls according to `policy`.
+///
+/// Under [`NullPolicy::Skip`] (and for leading nulls under [`NullPolicy::ForwardFill`]),
+/// `compute` only sees the non-null values and its results are scattered back into place.
+fn apply_null_policy<F>(
+    series: &Series,
+    policy: NullPolicy,
+    compute: F,
+) -> FeatureResult<Vec<Option<f64>>>
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+{
+    let resolved = match policy {
+        NullPolicy::Zero => {
+            return Ok(compute(&to_f64_vec(series)?)
+                .into_iter()
+                .map(Some)
+                .collect());
+        }
+        NullPolicy::Error => {
+            if series.null_count() > 0 {
+                return Err(FeatureError::NullValues {
+                    column: series.name().to_string(),
+                    nulls: series.null_count(),
+                });
+            }
+            series.cast(&DataType::Float64)?
+        }
+        NullPolicy::Skip => series.cast(&DataType::Float64)?,
+        NullPolicy::ForwardFill => series
+            .cast(&DataType::Float64)?
+            .fill_null(FillNullStrategy::Forward(None))?,
+    };
+
+    let options: Vec<Option<f64>> = resolved.f64()?.into_iter().collect();
+    let present: Vec<f64> = options.iter().flatten().copied().collect();
+    let mut computed = compute(&present).into_iter();
+    Ok(options
+        .iter()
+        .map(|value| value.and_then(|_| computed.next()))
+        .collect())
+}
+
+/// Running growth factor `prod(1 + r)` of `returns`, skipping non-finite values, together
+/// with how many were skipped.
+fn compounded_growth(returns: &[f64]) -> (Vec<f64>, usize) {
+    let mut path = Vec::with_capacity(returns.len());
+    let mut growth = 1.0;
+    let mut non_finite = 0;
+    for value in returns {
+        if value.is_finite() {
+            growth *= 1.0 + value;
+        } else {
+            non_finite += 1;
+        }
+        path.push(growth);
+    }
+
+    (path, non_finite)
+}
+
+fn daily_returns(prices: &[f64]) -> Vec<f64> {
+    let mut returns = Vec::with_capacity(prices.len());
+    if prices.is_empty() {
+        return returns;
+    }
+
+    returns.push(0.0);
+    for window in prices.windows(2) {
+        let prev = window[0];
+        let current = window[1];
+        let pct = if prev.abs() < f64::EPSILON {
+            0.0
+        } else {
+            (current / prev) - 1.0
+        };
+        returns.push(pct);
+    }
+
+    returns
+}
+
+/// Trailing `window`-row options that emit a value from the first row onwards.
+fn rolling_options(window: usize) -> RollingOptions {
+    RollingOptions {
+        window_size: Duration::new(window as i64),
+        min_periods: 1,
+        ..RollingOptions::default()
+    }
+}
+
+fn moving_average(prices: &[f64], window: usize) -> Vec<f64> {
+    let mut averages = Vec::with_capacity(prices.len());
+    let mut sum = 0.0;
+
+    for (idx, value) in prices.iter().enumerate() {
+        sum += value;
+        if idx >= window {
+            sum -= prices[idx - window];
+            averages.push(sum / window as f64);
+        } else {
+            averages.push(sum / (idx + 1) as f64);
+        }
+    }
+
+    averages
+}
+
+fn rolling_z_scores(values: &[f64], window: usize) -> Vec<f64> {
+    let mut zscores = Vec::with_capacity(values.len());
+    let mut moments = RollingMoments::new(window);
+
+    for value in values.iter() {
+        moments.push(*value);
+
+        let mean = moments.mean();
+        let std = moments.population_variance().sqrt();
+        let z = if std > f64::EPSILON {
+            (*value - mean) / std
+        } else {
+            0.0
+        };
+        zscores.push(z);
+    }
+
+    zscores
+}
+
+/// Monotonic-deque sliding extreme in O(n). `dominated(candidate, current)` returns true
+/// when an older `candidate` can never again be the window extreme once `current` arrives.
+fn rolling_extreme(values: &[f64], window: usize, dominated: fn(f64, f64) -> bool) -> Vec<f64> {
+    let mut extremes = Vec::with_capacity(values.len());
+    let mut candidates: VecDeque<usize> = VecDeque::with_capacity(window);
+
+    for (idx, value) in values.iter().enumerate() {
+        while candidates
+            .back()
+            .is_some_and(|&back| dominated(values[back], *value))
+        {
+            candidates.pop_back();
+        }
+        candidates.push_back(idx);
+
+        while candidates
+            .front()
+            .is_some_and(|&front| front + window <= idx)
+        {
+            candidates.pop_front();
+        }
+
+        extremes.push(values[candidates[0]]);
+    }
+
+    extremes
+}
+
+fn min_max_scale(value: f64, minimum: f64, maximum: f64, flat_rows: &mut usize) -> f64 {
+    let range = maximum - minimum;
+    if range > f64::EPSILON {
+        (value - minimum) / range
+    } else {
+        *flat_rows += 1;
+        0.5
+    }
+}
+
+fn exponential_moving_average(values: &[f64], span: usize) -> Vec<f64> {
+    let alpha = 2.0 / (span as f64 + 1.0);
+    let mut averages = Vec::with_capacity(values.len());
+    let mut previous: Option<f64> = None;
+
+    for value in values {
+        let current = match previous {
+            Some(prev) => alpha * value + (1.0 - alpha) * prev,
+            None => *value,
+        };
+        averages.push(current);
+        previous = Some(current);
+    }
+
+    averages
+}
+
+fn relative_strength_index(avg_gain: f64, avg_loss: f64) -> f64 {
+    if avg_loss <= f64::EPSILON {
+        if avg_gain <= f64::EPSILON {
+            50.0
+        } else {
+            100.0
+        }
+    } else {
+        100.0 - 100.0 / (1.0 + avg_gain / avg_loss)
+    }
+}
@@ -7,12 +7,31 @@ pub mod features;
 pub mod logging;
 pub mod metrics;
 
-pub use dataset::{DatasetError, MarketData};
-pub use features::{with_daily_returns, with_moving_average, with_z_score};
+pub use dataset::{
+    CalendarFill, CsvOptions, DatasetError, IntervalBounds, KeepStrategy, MarketData, write_parquet,
+};
+pub use features::{
+    FeatureError, FeaturePipeline, FeatureResult, NullPolicy, daily_returns_expr,
+    moving_average_expr, with_bollinger_bands, with_cross_sectional_rank,
+    with_cross_sectional_z_score, with_cumulative_return, with_daily_returns,
+    with_daily_returns_grouped, with_daily_returns_with_policy, with_ema, with_expanding_mean,
+    with_expanding_percentile_rank, with_expanding_std, with_forward_fill, with_lag,
+    with_lag_filled, with_log_returns, with_macd, with_min_max_scaled, with_moving_average,
+    with_moving_average_multi, with_moving_average_strict, with_moving_average_with_policy,
+    with_pct_change, with_reconstructed_price, with_residual, with_robust_z_score,
+    with_rolling_apply, with_rolling_beta, with_rolling_max, with_rolling_min,
+    with_rolling_min_max_scaled, with_rolling_quantile, with_rolling_std, with_rsi, with_vwap,
+    with_winsorized, with_z_score, with_z_score_with_policy, z_score_expr,
+};
 pub use metrics::{
-    AccumulationMode, AnalysisFrequency, FrequencyUnit, IndicatorMethod, MetricsError,
-    MetricsResult, PerformanceMetrics, indicator_analysis, indicator_analysis_with_method,
-    risk_analysis,
+    AccumulationMode, AnalysisFrequency, AnnualizationStyle, EvaluationOptions, FrequencyUnit,
+    ICMethod, IndicatorMethod, MetricsAccumulator, MetricsError, MetricsResult, PerformanceMetrics,
+    RiskReport, SERIES_LENGTH_TOLERANCE, beta_alpha, conditional_value_at_risk, correlation_matrix,
+    covariance_matrix, downside_deviation, drawdown_analysis, drawdown_series, ic_series,
+    indicator_analysis, indicator_analysis_grouped, indicator_analysis_with_method,
+    information_coefficient, martin_ratio, monthly_returns_table, omega_ratio, risk_analysis,
+    risk_analysis_relative, risk_analysis_with_benchmark, rolling_max_drawdown, rolling_metrics,
+    sortino_ratio, tail_ratio, turnover, ulcer_index, validate_series_length, value_at_risk,
 };
 
 pub type Result<T> = anyhow::Result<T>;
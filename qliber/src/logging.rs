@@ -1,14 +1,22 @@
-use std::sync::OnceLock;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
 
 use anyhow::anyhow;
 use chrono::{DateTime, Utc};
 use serde::Serialize;
-use tracing::info;
+use tracing::subscriber::DefaultGuard;
+use tracing::{Level, Subscriber, debug, error, info, trace, warn};
+use tracing_appender::rolling::{self, RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{EnvFilter, fmt};
 
 use crate::Result;
 
 static SUBSCRIBER: OnceLock<std::result::Result<(), String>> = OnceLock::new();
+static INCLUDE_DERIVED: AtomicBool = AtomicBool::new(true);
 
 const SHERLOCK_PROMPT: &str = "[Continuous skepticism (Sherlock Protocol)] Could this change affect unexpected files/systems? | Any hidden dependencies or cascades? | What edge cases and failure modes are unhandled? | If stuck, work backward from the desired outcome.";
 
@@ -24,7 +32,23 @@ pub struct LogEvent<'a> {
     pub db_phase: &'a str,
     pub method: &'a str,
     pub message: &'a str,
-    pub derived: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derived: Option<&'a str>,
+}
+
+/// Options applied by the first logging initialization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoggingConfig {
+    /// Attach the "Continuous skepticism" derived line to every record.
+    pub include_derived: bool,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            include_derived: true,
+        }
+    }
 }
 
 /// Initialize tracing subscriber emitting JSON records that follow the required schema.
@@ -32,15 +56,63 @@ pub struct LogEvent<'a> {
 /// Calling this function multiple times is safe; only the first invocation installs the
 /// subscriber.
 pub fn init_logging() -> Result<()> {
+    init_logging_with_config(LoggingConfig::default())
+}
+
+/// Like [`init_logging`], with explicit [`LoggingConfig`] options.
+pub fn init_logging_with_config(config: LoggingConfig) -> Result<()> {
+    install_subscriber(std::io::stdout, config)
+}
+
+/// Like [`init_logging`], but append the JSON records to the file at `path`.
+///
+/// The subscriber is process-wide: if logging was already initialized, this call is a no-op
+/// and records keep going to the original sink.
+pub fn init_logging_to_file<P: AsRef<Path>>(path: P) -> Result<()> {
+    let path = path.as_ref();
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("log path {} has no file name", path.display()))?;
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    install_subscriber(
+        rolling::never(directory, file_name),
+        LoggingConfig::default(),
+    )
+}
+
+/// Like [`init_logging_to_file`], but start a new `{file_prefix}.{date}` file in `directory`
+/// whenever `rotation` elapses.
+pub fn init_logging_to_rolling_file<P: AsRef<Path>>(
+    directory: P,
+    file_prefix: &str,
+    rotation: Rotation,
+) -> Result<()> {
+    install_subscriber(
+        RollingFileAppender::new(rotation, directory, file_prefix),
+        LoggingConfig::default(),
+    )
+}
+
+/// Like [`init_logging`], but send the JSON records to `writer`, e.g. a [`LogBuffer`].
+pub fn init_logging_to_writer<W>(writer: W) -> Result<()>
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    install_subscriber(writer, LoggingConfig::default())
+}
+
+fn install_subscriber<W>(writer: W, config: LoggingConfig) -> Result<()>
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
     let result = SUBSCRIBER.get_or_init(|| {
+        INCLUDE_DERIVED.store(config.include_derived, Ordering::Relaxed);
         let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-        fmt()
-            .with_env_filter(filter)
-            .json()
-            .with_current_span(false)
-            .with_span_list(false)
-            .with_timer(fmt::time::UtcTime::rfc_3339())
-            .with_target(false)
+        json_subscriber(writer, filter)
             .try_init()
             .map_err(|error| error.to_string())?;
 
@@ -53,8 +125,107 @@ pub fn init_logging() -> Result<()> {
     }
 }
 
+fn json_subscriber<W>(writer: W, filter: EnvFilter) -> impl Subscriber + Send + Sync + 'static
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    fmt()
+        .with_env_filter(filter)
+        .json()
+        .with_current_span(false)
+        .with_span_list(false)
+        .with_timer(fmt::time::UtcTime::rfc_3339())
+        .with_target(false)
+        .with_writer(writer)
+        .finish()
+}
+
+/// Shared in-memory sink for JSON log records.
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl LogBuffer {
+    /// Everything written so far, one JSON record per line.
+    pub fn contents(&self) -> String {
+        let bytes = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// The [`LogEvent`] payload of every captured [`log_event`] record.
+    pub fn events(&self) -> Vec<serde_json::Value> {
+        self.contents()
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter_map(|record| {
+                let json = record["fields"]["json"].as_str()?.to_string();
+                serde_json::from_str(&json).ok()
+            })
+            .collect()
+    }
+}
+
+impl Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogBuffer {
+    type Writer = LogBuffer;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Records captured by [`capture_logs`]; capturing stops when this is dropped.
+pub struct LogCapture {
+    buffer: LogBuffer,
+    _guard: DefaultGuard,
+}
+
+impl LogCapture {
+    pub fn buffer(&self) -> &LogBuffer {
+        &self.buffer
+    }
+
+    pub fn contents(&self) -> String {
+        self.buffer.contents()
+    }
+
+    pub fn events(&self) -> Vec<serde_json::Value> {
+        self.buffer.events()
+    }
+}
+
+/// Route `info`-and-above records emitted on the current thread into a [`LogBuffer`],
+/// overriding any global subscriber until the returned [`LogCapture`] is dropped.
+///
+/// Intended for tests; it leaves the process-wide initialization untouched.
+pub fn capture_logs() -> LogCapture {
+    let buffer = LogBuffer::default();
+    let guard =
+        tracing::subscriber::set_default(json_subscriber(buffer.clone(), EnvFilter::new("info")));
+
+    LogCapture {
+        buffer,
+        _guard: guard,
+    }
+}
+
 /// Emit a structured log event conforming to the canonical schema alongside the
 /// "Continuous skepticism" derived line required by the project guidelines.
+///
+/// Events are emitted at `info`; use [`log_event_at`] for hot paths that should log at a
+/// quieter level.
 #[allow(clippy::too_many_arguments)]
 pub fn log_event(
     filename: &str,
@@ -66,6 +237,34 @@ pub fn log_event(
     error: Option<&str>,
     db_phase: &str,
     method: &str,
+) {
+    log_event_at(
+        Level::INFO,
+        filename,
+        classname,
+        function,
+        system_section,
+        line_num,
+        message,
+        error,
+        db_phase,
+        method,
+    );
+}
+
+/// [`log_event`] emitted at an explicit `tracing` level.
+#[allow(clippy::too_many_arguments)]
+pub fn log_event_at(
+    level: Level,
+    filename: &str,
+    classname: &str,
+    function: &str,
+    system_section: &str,
+    line_num: u32,
+    message: &str,
+    error: Option<&str>,
+    db_phase: &str,
+    method: &str,
 ) {
     let event = LogEvent {
         filename,
@@ -78,12 +277,18 @@ pub fn log_event(
         db_phase,
         method,
         message,
-        derived: SHERLOCK_PROMPT,
+        derived: INCLUDE_DERIVED
+            .load(Ordering::Relaxed)
+            .then_some(SHERLOCK_PROMPT),
     };
 
-    if let Ok(serialized) = serde_json::to_string(&event) {
-        info!(target: "qliber", json = %serialized, derived = SHERLOCK_PROMPT);
-    } else {
-        info!(target: "qliber", message, derived = SHERLOCK_PROMPT);
+    let serialized = serde_json::to_string(&event).ok();
+    let json = serialized.as_deref().unwrap_or(message);
+    match level {
+        Level::TRACE => trace!(target: "qliber", json = %json, derived = event.derived),
+        Level::DEBUG => debug!(target: "qliber", json = %json, derived = event.derived),
+        Level::WARN => warn!(target: "qliber", json = %json, derived = event.derived),
+        Level::ERROR => error!(target: "qliber", json = %json, derived = event.derived),
+        _ => info!(target: "qliber", json = %json, derived = event.derived),
     }
 }
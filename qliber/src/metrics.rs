@@ -1,10 +1,14 @@
+use std::fmt;
 use std::str::FromStr;
 
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use polars::prelude::*;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tracing::Level;
 
-use crate::logging::log_event;
+use crate::logging::{log_event, log_event_at};
 
 #[derive(Debug, Error)]
 pub enum MetricsError {
@@ -24,34 +28,162 @@ pub enum MetricsError {
         "invalid indicator analysis method `{0}`; expected `mean`, `amount_weighted`, or `value_weighted`"
     )]
     InvalidIndicatorMethod(String),
+    #[error("returns have {returns} observations but benchmark has {benchmark}")]
+    LengthMismatch { returns: usize, benchmark: usize },
+    #[error("predictions have {predictions} observations but returns have {returns}")]
+    PredictionLengthMismatch { predictions: usize, returns: usize },
+    #[error("indicator analysis received an empty frame")]
+    EmptyFrame,
+    #[error("indicator column `{0}` has no finite values")]
+    AllNullColumn(String),
+    #[error("rolling window must be positive (got {0})")]
+    InvalidWindow(usize),
+    #[error("tail quantile must lie strictly between 0 and 0.5 (got {0})")]
+    InvalidTail(f64),
 }
 
 pub type MetricsResult<T> = Result<T, MetricsError>;
 
+/// Relative gap between observed and expected period counts tolerated by
+/// [`validate_series_length`].
+pub const SERIES_LENGTH_TOLERANCE: f64 = 0.05;
+
+const DAYS_PER_CALENDAR_YEAR: f64 = 365.25;
+
 /// Supported evaluation frequencies mirroring Qlib's `Freq` helper.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum FrequencyUnit {
     Minute,
     Day,
     Week,
     Month,
+    Quarter,
+    Year,
+}
+
+impl FrequencyUnit {
+    /// Trading minutes per day in Qlib's default calendar.
+    pub const MINUTES_PER_DAY: f64 = 240.0;
+    /// Trading days per year in Qlib's default calendar.
+    pub const DAYS_PER_YEAR: f64 = 238.0;
+    /// Trading weeks per year in Qlib's default calendar.
+    pub const WEEKS_PER_YEAR: f64 = 50.0;
+    pub const MONTHS_PER_YEAR: f64 = 12.0;
+    pub const QUARTERS_PER_YEAR: f64 = 4.0;
+
+    /// Periods of one unit per year, matching the scaler table of Qlib's `risk_analysis`.
+    pub const fn default_periods_per_year(self) -> f64 {
+        match self {
+            FrequencyUnit::Minute => Self::MINUTES_PER_DAY * Self::DAYS_PER_YEAR,
+            FrequencyUnit::Day => Self::DAYS_PER_YEAR,
+            FrequencyUnit::Week => Self::WEEKS_PER_YEAR,
+            FrequencyUnit::Month => Self::MONTHS_PER_YEAR,
+            FrequencyUnit::Quarter => Self::QUARTERS_PER_YEAR,
+            FrequencyUnit::Year => 1.0,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(from = "FrequencySpec")]
 pub struct AnalysisFrequency {
     count: u32,
     unit: FrequencyUnit,
+    #[serde(rename = "periods_per_year", skip_serializing_if = "Option::is_none")]
+    scaler: Option<f64>,
+}
+
+/// Serialized form of [`AnalysisFrequency`], routed through its constructors so a zero
+/// count is normalized on the way in.
+#[derive(Deserialize)]
+struct FrequencySpec {
+    count: u32,
+    unit: FrequencyUnit,
+    #[serde(default)]
+    periods_per_year: Option<f64>,
+}
+
+// Scalers compare bitwise so equality stays reflexive and `AnalysisFrequency` keeps `Eq`.
+impl PartialEq for AnalysisFrequency {
+    fn eq(&self, other: &Self) -> bool {
+        self.count == other.count
+            && self.unit == other.unit
+            && self.scaler.map(f64::to_bits) == other.scaler.map(f64::to_bits)
+    }
+}
+
+impl Eq for AnalysisFrequency {}
+
+impl From<FrequencySpec> for AnalysisFrequency {
+    fn from(spec: FrequencySpec) -> Self {
+        match spec.periods_per_year {
+            Some(scaler) => Self::with_scaler(spec.count, spec.unit, scaler),
+            None => Self::new(spec.count, spec.unit),
+        }
+    }
 }
 
 impl AnalysisFrequency {
+    pub const DAILY: Self = Self::preset(FrequencyUnit::Day);
+    pub const WEEKLY: Self = Self::preset(FrequencyUnit::Week);
+    pub const MONTHLY: Self = Self::preset(FrequencyUnit::Month);
+    pub const QUARTERLY: Self = Self::preset(FrequencyUnit::Quarter);
+    pub const YEARLY: Self = Self::preset(FrequencyUnit::Year);
+
+    const fn preset(unit: FrequencyUnit) -> Self {
+        Self {
+            count: 1,
+            unit,
+            scaler: None,
+        }
+    }
+
+    pub fn daily() -> Self {
+        Self::DAILY
+    }
+
+    pub fn weekly() -> Self {
+        Self::WEEKLY
+    }
+
+    pub fn monthly() -> Self {
+        Self::MONTHLY
+    }
+
+    /// `count`-minute bars on the default Qlib calendar; see [`AnalysisFrequency::minute`]
+    /// for custom session lengths.
+    pub fn minutes(count: u32) -> Self {
+        Self::new(count, FrequencyUnit::Minute)
+    }
+
     pub fn new(count: u32, unit: FrequencyUnit) -> Self {
         let normalized_count = count.max(1);
         Self {
             count: normalized_count,
             unit,
+            scaler: None,
+        }
+    }
+
+    /// Build a frequency whose annualization factor is `periods_per_year` verbatim instead of
+    /// the Qlib scaler table, e.g. 365 daily periods for crypto calendars. `count` is kept for
+    /// [`AnalysisFrequency::count`] and serialization but does not divide the scaler.
+    pub fn with_scaler(count: u32, unit: FrequencyUnit, periods_per_year: f64) -> Self {
+        Self {
+            scaler: Some(periods_per_year),
+            ..Self::new(count, unit)
         }
     }
 
+    /// One-minute bars annualized as `minutes_per_day * trading_days` periods, for markets
+    /// whose session differs from the default 240-minute, 238-day calendar (e.g. 1440 and
+    /// 365 for round-the-clock crypto venues).
+    pub fn minute(minutes_per_day: u32, trading_days: u32) -> Self {
+        let periods_per_year = f64::from(minutes_per_day) * f64::from(trading_days);
+        Self::with_scaler(1, FrequencyUnit::Minute, periods_per_year)
+    }
+
     pub fn unit(&self) -> FrequencyUnit {
         self.unit
     }
@@ -61,16 +193,60 @@ impl AnalysisFrequency {
     }
 
     pub fn periods_per_year(&self) -> f64 {
-        let scaler = match self.unit {
-            FrequencyUnit::Minute => 240.0 * 238.0,
-            FrequencyUnit::Day => 238.0,
-            FrequencyUnit::Week => 50.0,
-            FrequencyUnit::Month => 12.0,
+        if let Some(scaler) = self.scaler {
+            return scaler;
+        }
+
+        self.unit.default_periods_per_year() / self.count as f64
+    }
+
+    /// Number of periods of this frequency expected on the dates `start..=end`, counted on
+    /// the frequency's own calendar: weekdays for daily and minute bars (240 sessions a
+    /// day), calendar weeks, months, quarters or years otherwise. An explicit scaler is
+    /// spread over every calendar day, as for round-the-clock venues. Reversed bounds
+    /// yield 0.0.
+    pub fn expected_periods(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> f64 {
+        let (first, last) = (start.date_naive(), end.date_naive());
+        if last < first {
+            return 0.0;
+        }
+
+        if let Some(scaler) = self.scaler {
+            let days = (last - first).num_days() + 1;
+            return days as f64 / DAYS_PER_CALENDAR_YEAR * scaler;
+        }
+
+        let month_index = |date: NaiveDate| date.year() * 12 + date.month0() as i32;
+        let periods = match self.unit {
+            FrequencyUnit::Minute => {
+                weekdays_between(first, last) as f64 * FrequencyUnit::MINUTES_PER_DAY
+            }
+            FrequencyUnit::Day => weekdays_between(first, last) as f64,
+            FrequencyUnit::Week => {
+                let week_start = |date: NaiveDate| {
+                    date.num_days_from_ce() - date.weekday().num_days_from_monday() as i32
+                };
+                ((week_start(last) - week_start(first)) / 7 + 1) as f64
+            }
+            FrequencyUnit::Month => (month_index(last) - month_index(first) + 1) as f64,
+            FrequencyUnit::Quarter => (month_index(last) / 3 - month_index(first) / 3 + 1) as f64,
+            FrequencyUnit::Year => (last.year() - first.year() + 1) as f64,
         };
-        scaler / self.count as f64
+        periods / self.count as f64
     }
 }
 
+/// Monday-to-Friday dates in `first..=last`.
+fn weekdays_between(first: NaiveDate, last: NaiveDate) -> i64 {
+    let days = (last - first).num_days() + 1;
+    let leading = first.weekday().num_days_from_monday() as i64;
+    let remainder = days % 7;
+    let partial = (0..remainder)
+        .filter(|offset| (leading + offset) % 7 < 5)
+        .count() as i64;
+    days / 7 * 5 + partial
+}
+
 impl FromStr for AnalysisFrequency {
     type Err = MetricsError;
 
@@ -92,6 +268,8 @@ impl FromStr for AnalysisFrequency {
         };
 
         let unit = match suffix {
+            "year" | "y" => FrequencyUnit::Year,
+            "quarter" | "q" => FrequencyUnit::Quarter,
             "month" | "mon" => FrequencyUnit::Month,
             "week" | "w" => FrequencyUnit::Week,
             "day" | "d" => FrequencyUnit::Day,
@@ -112,15 +290,29 @@ impl TryFrom<&str> for AnalysisFrequency {
 }
 
 /// Indicator weighting strategies matching Qlib's `indicator_analysis` helper.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Parses from `mean`, `amount_weighted` (or `amount`) and `value_weighted` (or `value`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum IndicatorMethod {
     Mean,
     AmountWeighted,
     ValueWeighted,
 }
 
+/// Correlation used by [`information_coefficient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ICMethod {
+    /// Linear correlation of the raw values.
+    Pearson,
+    /// Pearson correlation of the ranks (rank IC), with ties sharing their average rank.
+    Spearman,
+}
+
 /// Controls how returns are accumulated when computing performance statistics.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AccumulationMode {
     /// Arithmetic accumulation that mirrors Qlib's `mode="sum"` risk analysis.
     #[default]
@@ -141,20 +333,69 @@ impl FromStr for AccumulationMode {
     }
 }
 
+impl fmt::Display for AccumulationMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AccumulationMode::Sum => "sum",
+            AccumulationMode::Product => "product",
+        })
+    }
+}
+
 impl FromStr for IndicatorMethod {
     type Err = MetricsError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.trim().to_lowercase().as_str() {
             "mean" => Ok(IndicatorMethod::Mean),
-            "amount_weighted" => Ok(IndicatorMethod::AmountWeighted),
-            "value_weighted" => Ok(IndicatorMethod::ValueWeighted),
+            "amount_weighted" | "amount" => Ok(IndicatorMethod::AmountWeighted),
+            "value_weighted" | "value" => Ok(IndicatorMethod::ValueWeighted),
             other => Err(MetricsError::InvalidIndicatorMethod(other.to_string())),
         }
     }
 }
 
+/// How sum-mode evaluation scales the mean return to a year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnualizationStyle {
+    /// `mean * periods_per_year`, Qlib's arithmetic convention.
+    #[default]
+    Simple,
+    /// `(1 + mean)^periods_per_year - 1`.
+    Compounded,
+}
+
+/// Tunable inputs for [`PerformanceMetrics::evaluate_with_options`].
 #[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvaluationOptions {
+    /// Per-period rate subtracted from the mean in the Sharpe ratio.
+    pub risk_free_rate: f64,
+    /// Annualization of the sum-mode mean; product mode always compounds.
+    pub annualization: AnnualizationStyle,
+    /// Delta degrees of freedom of the std estimator: 1 for the sample std (the default,
+    /// pandas' convention), 0 for the population std (numpy's default).
+    pub ddof: usize,
+}
+
+impl Default for EvaluationOptions {
+    fn default() -> Self {
+        Self {
+            risk_free_rate: 0.0,
+            annualization: AnnualizationStyle::default(),
+            ddof: 1,
+        }
+    }
+}
+
+/// Summary statistics of a returns series.
+///
+/// A single finite return `r` evaluates to `mean_return == cumulative_return == r` in
+/// both modes, with zero `std_dev`, `annualized_volatility`, `sharpe_ratio` and
+/// `information_ratio` since one observation carries no dispersion. Drawdowns keep each
+/// mode's peak convention: sum mode measures from zero, so a lone loss is its own
+/// drawdown, while product mode measures from the first equity value and reports none.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
     pub mean_return: f64,
     pub std_dev: f64,
@@ -164,6 +405,9 @@ pub struct PerformanceMetrics {
     pub sharpe_ratio: f64,
     pub information_ratio: f64,
     pub max_drawdown: f64,
+    pub calmar_ratio: f64,
+    /// Longest stretch of consecutive periods spent below a prior equity peak.
+    pub max_drawdown_duration: usize,
 }
 
 impl PerformanceMetrics {
@@ -175,6 +419,33 @@ impl PerformanceMetrics {
         returns: &[f64],
         periods_per_year: f64,
         mode: AccumulationMode,
+    ) -> Self {
+        Self::evaluate_with_risk_free(returns, periods_per_year, mode, 0.0)
+    }
+
+    /// Evaluate returns while subtracting a per-period risk-free rate from the Sharpe ratio.
+    ///
+    /// The rate must use the same periodicity as `returns` (e.g. a daily rate for daily
+    /// returns). The information ratio keeps using the raw mean return.
+    pub fn evaluate_with_risk_free(
+        returns: &[f64],
+        periods_per_year: f64,
+        mode: AccumulationMode,
+        risk_free_rate: f64,
+    ) -> Self {
+        let options = EvaluationOptions {
+            risk_free_rate,
+            ..EvaluationOptions::default()
+        };
+        Self::evaluate_with_options(returns, periods_per_year, mode, &options)
+    }
+
+    /// Evaluate returns with every tunable knob spelled out in [`EvaluationOptions`].
+    pub fn evaluate_with_options(
+        returns: &[f64],
+        periods_per_year: f64,
+        mode: AccumulationMode,
+        options: &EvaluationOptions,
     ) -> Self {
         let (clean_returns, filtered_out) = sanitize_returns(returns);
 
@@ -182,7 +453,7 @@ impl PerformanceMetrics {
             log_event(
                 file!(),
                 "PerformanceMetrics",
-                "evaluate_with_mode",
+                "evaluate_with_options",
                 "metrics.evaluate",
                 line!(),
                 "Received empty or non-finite returns; returning zeroed metrics",
@@ -190,23 +461,14 @@ impl PerformanceMetrics {
                 "none",
                 "GET",
             );
-            return Self {
-                mean_return: 0.0,
-                std_dev: 0.0,
-                cumulative_return: 0.0,
-                annualized_return: 0.0,
-                annualized_volatility: 0.0,
-                sharpe_ratio: 0.0,
-                information_ratio: 0.0,
-                max_drawdown: 0.0,
-            };
+            return Self::zeroed();
         }
 
         if filtered_out > 0 {
             log_event(
                 file!(),
                 "PerformanceMetrics",
-                "evaluate_with_mode",
+                "evaluate_with_options",
                 "metrics.evaluate",
                 line!(),
                 &format!("Filtered {filtered_out} non-finite returns prior to evaluation"),
@@ -216,10 +478,95 @@ impl PerformanceMetrics {
             );
         }
 
-        match mode {
-            AccumulationMode::Sum => Self::from_sum_mode(&clean_returns, periods_per_year),
-            AccumulationMode::Product => Self::from_product_mode(&clean_returns, periods_per_year),
-        }
+        let curve = equity_curve(&clean_returns, mode);
+        Self::from_curve(&clean_returns, &curve, periods_per_year, mode, options)
+    }
+
+    /// Evaluate the returns held in `return_column` of `frame`.
+    ///
+    /// The column is cast to f64; nulls and non-finite values are dropped before
+    /// evaluation just like the slice-based entry points.
+    pub fn evaluate_from_frame(
+        frame: &DataFrame,
+        return_column: &str,
+        periods_per_year: f64,
+        mode: AccumulationMode,
+    ) -> MetricsResult<Self> {
+        let column = require_column(frame, return_column).inspect_err(|error| {
+            log_event(
+                file!(),
+                "PerformanceMetrics",
+                "evaluate_from_frame",
+                "metrics.evaluate",
+                line!(),
+                &format!("Missing `{return_column}` column required for evaluation"),
+                Some(&error.to_string()),
+                "none",
+                "GET",
+            );
+        })?;
+        let returns: Vec<f64> = column.into_iter().flatten().collect();
+
+        log_event(
+            file!(),
+            "PerformanceMetrics",
+            "evaluate_from_frame",
+            "metrics.evaluate",
+            line!(),
+            &format!(
+                "Extracted {} returns from `{return_column}` ({} nulls)",
+                returns.len(),
+                column.null_count()
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(Self::evaluate_with_mode(&returns, periods_per_year, mode))
+    }
+
+    /// Evaluate returns and also hand back the equity curve the metrics were derived from.
+    ///
+    /// The curve is the running sum of returns in sum mode and the cumulative growth of
+    /// one unit in product mode. Non-finite returns are dropped before either is built, so
+    /// the curve may be shorter than the input and is empty when nothing finite remains.
+    pub fn evaluate_with_curve(
+        returns: &[f64],
+        periods_per_year: f64,
+        mode: AccumulationMode,
+    ) -> (Self, Vec<f64>) {
+        let (clean_returns, filtered_out) = sanitize_returns(returns);
+        let curve = equity_curve(&clean_returns, mode);
+        let metrics = if clean_returns.is_empty() {
+            Self::zeroed()
+        } else {
+            Self::from_curve(
+                &clean_returns,
+                &curve,
+                periods_per_year,
+                mode,
+                &EvaluationOptions::default(),
+            )
+        };
+
+        log_event(
+            file!(),
+            "PerformanceMetrics",
+            "evaluate_with_curve",
+            "metrics.evaluate",
+            line!(),
+            &format!(
+                "Evaluated {} curve points using {:?} mode (filtered {filtered_out} non-finite returns)",
+                curve.len(),
+                mode
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        (metrics, curve)
     }
 
     pub fn evaluate_with_frequency(
@@ -331,29 +678,57 @@ impl PerformanceMetrics {
         }
     }
 
-    fn from_sum_mode(returns: &[f64], periods_per_year: f64) -> Self {
+    fn zeroed() -> Self {
+        Self {
+            mean_return: 0.0,
+            std_dev: 0.0,
+            cumulative_return: 0.0,
+            annualized_return: 0.0,
+            annualized_volatility: 0.0,
+            sharpe_ratio: 0.0,
+            information_ratio: 0.0,
+            max_drawdown: 0.0,
+            calmar_ratio: 0.0,
+            max_drawdown_duration: 0,
+        }
+    }
+
+    fn from_curve(
+        returns: &[f64],
+        curve: &[f64],
+        periods_per_year: f64,
+        mode: AccumulationMode,
+        options: &EvaluationOptions,
+    ) -> Self {
+        match mode {
+            AccumulationMode::Sum => Self::from_sum_mode(returns, curve, periods_per_year, options),
+            AccumulationMode::Product => {
+                Self::from_product_mode(returns, curve, periods_per_year, options)
+            }
+        }
+    }
+
+    fn from_sum_mode(
+        returns: &[f64],
+        running_sum: &[f64],
+        periods_per_year: f64,
+        options: &EvaluationOptions,
+    ) -> Self {
         let count = returns.len() as f64;
         let mean = returns.iter().copied().sum::<f64>() / count;
-        let variance = sample_variance(returns, mean);
+        let variance = variance_with_ddof(returns, mean, options.ddof);
         let std_dev = variance.sqrt();
 
         let cumulative_return = returns.iter().copied().sum::<f64>();
-        let annualized_return = mean * periods_per_year;
+        let annualized_return = match options.annualization {
+            AnnualizationStyle::Simple => mean * periods_per_year,
+            AnnualizationStyle::Compounded => (1.0 + mean).powf(periods_per_year) - 1.0,
+        };
         let annualized_volatility = std_dev * periods_per_year.sqrt();
 
-        let mut running_sum = 0.0;
-        let mut running_peak = 0.0;
-        let mut max_drawdown = 0.0;
-        for value in returns {
-            running_sum += value;
-            if running_sum > running_peak {
-                running_peak = running_sum;
-            }
-            let drawdown = running_sum - running_peak;
-            if drawdown < max_drawdown {
-                max_drawdown = drawdown;
-            }
-        }
+        let underwater = drawdowns(running_sum, AccumulationMode::Sum);
+        let max_drawdown = deepest_drawdown(&underwater);
+        let max_drawdown_duration = longest_drawdown_duration(&underwater);
 
         let scaling = periods_per_year.sqrt();
         let information_ratio = if std_dev > f64::EPSILON {
@@ -361,6 +736,12 @@ impl PerformanceMetrics {
         } else {
             0.0
         };
+        let sharpe_ratio = if std_dev > f64::EPSILON {
+            ((mean - options.risk_free_rate) / std_dev) * scaling
+        } else {
+            0.0
+        };
+        let calmar_ratio = calmar_ratio(annualized_return, max_drawdown);
 
         log_event(
             file!(),
@@ -380,20 +761,20 @@ impl PerformanceMetrics {
             cumulative_return,
             annualized_return,
             annualized_volatility,
-            sharpe_ratio: information_ratio,
+            sharpe_ratio,
             information_ratio,
             max_drawdown,
+            calmar_ratio,
+            max_drawdown_duration,
         }
     }
 
-    fn from_product_mode(returns: &[f64], periods_per_year: f64) -> Self {
-        let mut cumulative_curve = Vec::with_capacity(returns.len());
-        let mut cumulative_product = 1.0;
-        for value in returns {
-            cumulative_product *= 1.0 + value;
-            cumulative_curve.push(cumulative_product);
-        }
-
+    fn from_product_mode(
+        returns: &[f64],
+        cumulative_curve: &[f64],
+        periods_per_year: f64,
+        options: &EvaluationOptions,
+    ) -> Self {
         let final_value = *cumulative_curve.last().unwrap_or(&1.0);
         let count = returns.len() as f64;
         let cumulative_return = final_value - 1.0;
@@ -410,7 +791,8 @@ impl PerformanceMetrics {
                 if base.is_sign_positive() && base > f64::EPSILON {
                     Some(base.ln())
                 } else {
-                    log_event(
+                    log_event_at(
+                        Level::DEBUG,
                         file!(),
                         "PerformanceMetrics",
                         "from_product_mode",
@@ -431,7 +813,7 @@ impl PerformanceMetrics {
 
         let std_dev = if log_returns.len() > 1 {
             let log_mean = log_returns.iter().copied().sum::<f64>() / log_returns.len() as f64;
-            sample_variance(&log_returns, log_mean).sqrt()
+            variance_with_ddof(&log_returns, log_mean, options.ddof).sqrt()
         } else {
             0.0
         };
@@ -443,17 +825,9 @@ impl PerformanceMetrics {
         };
         let annualized_volatility = std_dev * periods_per_year.sqrt();
 
-        let mut max_drawdown = 0.0;
-        let mut peak = cumulative_curve.first().copied().unwrap_or(1.0);
-        for value in &cumulative_curve {
-            if *value > peak {
-                peak = *value;
-            }
-            let drawdown = (value / peak) - 1.0;
-            if drawdown < max_drawdown {
-                max_drawdown = drawdown;
-            }
-        }
+        let underwater = drawdowns(cumulative_curve, AccumulationMode::Product);
+        let max_drawdown = deepest_drawdown(&underwater);
+        let max_drawdown_duration = longest_drawdown_duration(&underwater);
 
         let scaling = periods_per_year.sqrt();
         let information_ratio = if std_dev > f64::EPSILON {
@@ -461,6 +835,12 @@ impl PerformanceMetrics {
         } else {
             0.0
         };
+        let sharpe_ratio = if std_dev > f64::EPSILON {
+            ((mean - options.risk_free_rate) / std_dev) * scaling
+        } else {
+            0.0
+        };
+        let calmar_ratio = calmar_ratio(annualized_return, max_drawdown);
 
         log_event(
             file!(),
@@ -480,9 +860,11 @@ impl PerformanceMetrics {
             cumulative_return,
             annualized_return,
             annualized_volatility,
-            sharpe_ratio: information_ratio,
+            sharpe_ratio,
             information_ratio,
             max_drawdown,
+            calmar_ratio,
+            max_drawdown_duration,
         }
     }
 
@@ -491,15 +873,19 @@ impl PerformanceMetrics {
             "mean",
             "std",
             "annualized_return",
+            "sharpe",
             "information_ratio",
             "max_drawdown",
+            "calmar",
         ];
         let values = [
             self.mean_return,
             self.std_dev,
             self.annualized_return,
+            self.sharpe_ratio,
             self.information_ratio,
             self.max_drawdown,
+            self.calmar_ratio,
         ];
 
         let frame = DataFrame::new(vec![
@@ -523,78 +909,438 @@ impl PerformanceMetrics {
     }
 }
 
-pub fn indicator_analysis(frame: &DataFrame, method: IndicatorMethod) -> MetricsResult<DataFrame> {
-    let count_weights = match require_column(frame, "count") {
-        Ok(column) => column,
-        Err(error) => {
-            log_event(
-                file!(),
-                "PerformanceMetrics",
-                "indicator_analysis",
-                "metrics.indicator",
-                line!(),
-                "Missing required `count` column for indicator analysis",
-                Some(&error.to_string()),
-                "none",
-                "GET",
-            );
-            return Err(error);
-        }
-    };
+impl fmt::Display for PerformanceMetrics {
+    /// Aligned two-column table; return, volatility and drawdown rows are percentages and
+    /// ratios are unitless.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |value: f64| format!("{}%", significant(value * 100.0));
+        let ratio = significant;
+        let rows = [
+            ("Mean return", percent(self.mean_return)),
+            ("Std dev", percent(self.std_dev)),
+            ("Cumulative return", percent(self.cumulative_return)),
+            ("Annualized return", percent(self.annualized_return)),
+            ("Annualized volatility", percent(self.annualized_volatility)),
+            ("Sharpe ratio", ratio(self.sharpe_ratio)),
+            ("Information ratio", ratio(self.information_ratio)),
+            ("Max drawdown", percent(self.max_drawdown)),
+            ("Calmar ratio", ratio(self.calmar_ratio)),
+            (
+                "Max drawdown duration",
+                format!("{} periods", self.max_drawdown_duration),
+            ),
+        ];
 
-    let weights = match method {
-        IndicatorMethod::Mean => count_weights.clone(),
-        IndicatorMethod::AmountWeighted => match require_column(frame, "deal_amount") {
-            Ok(column) => column,
-            Err(error) => {
-                log_event(
-                    file!(),
-                    "PerformanceMetrics",
-                    "indicator_analysis",
-                    "metrics.indicator",
-                    line!(),
-                    "Missing `deal_amount` column required for amount-weighted analysis",
-                    Some(&error.to_string()),
-                    "none",
-                    "GET",
-                );
-                return Err(error);
+        for (index, (label, value)) in rows.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
             }
-        },
-        IndicatorMethod::ValueWeighted => match require_column(frame, "value") {
-            Ok(column) => column,
-            Err(error) => {
-                log_event(
-                    file!(),
-                    "PerformanceMetrics",
-                    "indicator_analysis",
-                    "metrics.indicator",
-                    line!(),
-                    "Missing `value` column required for value-weighted analysis",
-                    Some(&error.to_string()),
-                    "none",
-                    "GET",
-                );
-                return Err(error);
+            write!(f, "{label:<22}{value:>16}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Significant digits shown per value in the [`PerformanceMetrics`] table.
+const DISPLAY_SIGNIFICANT_DIGITS: usize = 5;
+
+/// `value` rounded to [`DISPLAY_SIGNIFICANT_DIGITS`] significant digits, in scientific
+/// notation when that would need leading zeros past the fourth decimal or more integer
+/// digits than are significant.
+fn significant(value: f64) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return format!("{value}");
+    }
+
+    let precision = DISPLAY_SIGNIFICANT_DIGITS - 1;
+    let scientific = format!("{value:.precision$e}");
+    let exponent: i32 = scientific
+        .rsplit_once('e')
+        .and_then(|(_, exponent)| exponent.parse().ok())
+        .unwrap_or(0);
+    if exponent < -4 || exponent > precision as i32 {
+        return scientific;
+    }
+
+    let decimals = (precision as i32 - exponent) as usize;
+    format!("{value:.decimals$}")
+}
+
+/// Every scalar risk statistic for a return series, computed in one pass.
+///
+/// VaR and CVaR use 95% confidence. `beta` and `alpha` are only present when a benchmark
+/// is supplied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskReport {
+    pub metrics: PerformanceMetrics,
+    pub value_at_risk: f64,
+    pub conditional_value_at_risk: f64,
+    pub sortino_ratio: f64,
+    pub beta: Option<f64>,
+    pub alpha: Option<f64>,
+}
+
+impl RiskReport {
+    pub const CONFIDENCE: f64 = 0.95;
+
+    /// Build the report; a benchmark of different length yields
+    /// [`MetricsError::LengthMismatch`].
+    pub fn generate(
+        returns: &[f64],
+        benchmark: Option<&[f64]>,
+        periods_per_year: f64,
+        mode: AccumulationMode,
+    ) -> MetricsResult<Self> {
+        let (beta, alpha) = match benchmark {
+            Some(benchmark) => {
+                let (beta, alpha) = beta_alpha(returns, benchmark, periods_per_year)?;
+                (Some(beta), Some(alpha))
             }
-        },
-    };
+            None => (None, None),
+        };
+
+        let report = Self {
+            metrics: PerformanceMetrics::evaluate_with_mode(returns, periods_per_year, mode),
+            value_at_risk: value_at_risk(returns, Self::CONFIDENCE),
+            conditional_value_at_risk: conditional_value_at_risk(returns, Self::CONFIDENCE),
+            sortino_ratio: sortino_ratio(returns, periods_per_year),
+            beta,
+            alpha,
+        };
 
-    let ffr_values = require_column(frame, "ffr").inspect_err(|error| {
         log_event(
             file!(),
-            "PerformanceMetrics",
-            "indicator_analysis",
-            "metrics.indicator",
+            "RiskReport",
+            "generate",
+            "metrics.evaluate",
             line!(),
-            "Missing `ffr` column required for indicator analysis",
-            Some(&error.to_string()),
+            &format!(
+                "Generated risk report using {:?} mode (benchmark supplied: {})",
+                mode,
+                benchmark.is_some()
+            ),
+            None,
             "none",
             "GET",
         );
-    })?;
-    let pa_values = require_column(frame, "pa").inspect_err(|error| {
-        log_event(
+
+        Ok(report)
+    }
+
+    /// The report in the `metric`/`risk` layout of [`risk_analysis`], with the extra
+    /// statistics appended as further rows.
+    pub fn to_dataframe(&self) -> MetricsResult<DataFrame> {
+        let base = self.metrics.to_risk_dataframe()?;
+        let mut metrics: Vec<String> = base
+            .column("metric")?
+            .utf8()?
+            .into_no_null_iter()
+            .map(str::to_string)
+            .collect();
+        let mut values: Vec<f64> = base.column("risk")?.f64()?.into_no_null_iter().collect();
+
+        let extras = [
+            ("value_at_risk", Some(self.value_at_risk)),
+            (
+                "conditional_value_at_risk",
+                Some(self.conditional_value_at_risk),
+            ),
+            ("sortino", Some(self.sortino_ratio)),
+            ("beta", self.beta),
+            ("alpha", self.alpha),
+        ];
+        for (name, value) in extras {
+            if let Some(value) = value {
+                metrics.push(name.to_string());
+                values.push(value);
+            }
+        }
+
+        let frame = DataFrame::new(vec![
+            Series::new("metric", metrics),
+            Series::new("risk", values),
+        ])?;
+
+        log_event(
+            file!(),
+            "RiskReport",
+            "to_dataframe",
+            "metrics.evaluate",
+            line!(),
+            &format!("Converted risk report into {} rows", frame.height()),
+            None,
+            "none",
+            "GET",
+        );
+
+        Ok(frame)
+    }
+}
+
+/// Running [`PerformanceMetrics`] for returns that arrive one at a time.
+///
+/// Only moments, the equity level and the drawdown state are kept, so each [`push`] is O(1)
+/// while [`snapshot`] matches [`PerformanceMetrics::evaluate_with_mode`] over every pushed
+/// return with default [`EvaluationOptions`], up to floating-point rounding.
+///
+/// [`push`]: MetricsAccumulator::push
+/// [`snapshot`]: MetricsAccumulator::snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsAccumulator {
+    mode: AccumulationMode,
+    count: usize,
+    sum: f64,
+    sum_squares: f64,
+    log_count: usize,
+    log_sum: f64,
+    log_sum_squares: f64,
+    cumulative_product: f64,
+    peak: f64,
+    max_drawdown: f64,
+    drawdown_run: usize,
+    max_drawdown_duration: usize,
+}
+
+impl Default for MetricsAccumulator {
+    fn default() -> Self {
+        Self::new(AccumulationMode::default())
+    }
+}
+
+impl MetricsAccumulator {
+    pub fn new(mode: AccumulationMode) -> Self {
+        Self {
+            mode,
+            count: 0,
+            sum: 0.0,
+            sum_squares: 0.0,
+            log_count: 0,
+            log_sum: 0.0,
+            log_sum_squares: 0.0,
+            cumulative_product: 1.0,
+            peak: 0.0,
+            max_drawdown: 0.0,
+            drawdown_run: 0,
+            max_drawdown_duration: 0,
+        }
+    }
+
+    /// Number of finite returns pushed so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Fold one return into the running state. Non-finite values are ignored and logged.
+    pub fn push(&mut self, return_value: f64) {
+        if !return_value.is_finite() {
+            log_event(
+                file!(),
+                "MetricsAccumulator",
+                "push",
+                "metrics.evaluate",
+                line!(),
+                &format!("Ignoring non-finite return {return_value}"),
+                None,
+                "none",
+                "GET",
+            );
+            return;
+        }
+
+        self.count += 1;
+        self.sum += return_value;
+        self.sum_squares += return_value * return_value;
+        self.cumulative_product *= 1.0 + return_value;
+        let gross = 1.0 + return_value;
+        if gross.is_sign_positive() && gross > f64::EPSILON {
+            let log_return = gross.ln();
+            self.log_count += 1;
+            self.log_sum += log_return;
+            self.log_sum_squares += log_return * log_return;
+        }
+
+        let drawdown = match self.mode {
+            AccumulationMode::Sum => {
+                self.peak = self.peak.max(self.sum);
+                self.sum - self.peak
+            }
+            AccumulationMode::Product => {
+                if self.count == 1 {
+                    self.peak = self.cumulative_product;
+                }
+                self.peak = self.peak.max(self.cumulative_product);
+                self.cumulative_product / self.peak - 1.0
+            }
+        };
+        self.max_drawdown = self.max_drawdown.min(drawdown);
+        if drawdown < 0.0 {
+            self.drawdown_run += 1;
+            self.max_drawdown_duration = self.max_drawdown_duration.max(self.drawdown_run);
+        } else {
+            self.drawdown_run = 0;
+        }
+    }
+
+    /// Metrics over every return pushed so far, annualized with `periods_per_year`.
+    pub fn snapshot(&self, periods_per_year: f64) -> PerformanceMetrics {
+        if self.count == 0 {
+            log_event(
+                file!(),
+                "MetricsAccumulator",
+                "snapshot",
+                "metrics.evaluate",
+                line!(),
+                "No returns accumulated; returning zeroed metrics",
+                None,
+                "none",
+                "GET",
+            );
+            return PerformanceMetrics::zeroed();
+        }
+
+        let count = self.count as f64;
+        let (mean, std_dev, cumulative_return, annualized_return) = match self.mode {
+            AccumulationMode::Sum => {
+                let mean = self.sum / count;
+                (
+                    mean,
+                    moment_std(self.count, self.sum, self.sum_squares),
+                    self.sum,
+                    mean * periods_per_year,
+                )
+            }
+            AccumulationMode::Product => (
+                self.cumulative_product.powf(1.0 / count) - 1.0,
+                moment_std(self.log_count, self.log_sum, self.log_sum_squares),
+                self.cumulative_product - 1.0,
+                self.cumulative_product.powf(periods_per_year / count) - 1.0,
+            ),
+        };
+        let ratio = if std_dev > f64::EPSILON {
+            mean / std_dev * periods_per_year.sqrt()
+        } else {
+            0.0
+        };
+
+        log_event(
+            file!(),
+            "MetricsAccumulator",
+            "snapshot",
+            "metrics.evaluate",
+            line!(),
+            &format!(
+                "Snapshot of {} accumulated {:?} returns",
+                self.count, self.mode
+            ),
+            None,
+            "none",
+            "GET",
+        );
+
+        PerformanceMetrics {
+            mean_return: mean,
+            std_dev,
+            cumulative_return,
+            annualized_return,
+            annualized_volatility: std_dev * periods_per_year.sqrt(),
+            sharpe_ratio: ratio,
+            information_ratio: ratio,
+            max_drawdown: self.max_drawdown,
+            calmar_ratio: calmar_ratio(annualized_return, self.max_drawdown),
+            max_drawdown_duration: self.max_drawdown_duration,
+        }
+    }
+}
+
+/// Weighted `ffr`, `pa` and `pos` indicators, one row each, with the `weight_sum` that backs
+/// every estimate. `pos` is always count-weighted.
+pub fn indicator_analysis(frame: &DataFrame, method: IndicatorMethod) -> MetricsResult<DataFrame> {
+    if frame.height() == 0 {
+        log_event(
+            file!(),
+            "PerformanceMetrics",
+            "indicator_analysis",
+            "metrics.indicator",
+            line!(),
+            "Received an empty frame for indicator analysis",
+            Some(&MetricsError::EmptyFrame.to_string()),
+            "none",
+            "GET",
+        );
+        return Err(MetricsError::EmptyFrame);
+    }
+
+    let count_weights = match require_column(frame, "count") {
+        Ok(column) => column,
+        Err(error) => {
+            log_event(
+                file!(),
+                "PerformanceMetrics",
+                "indicator_analysis",
+                "metrics.indicator",
+                line!(),
+                "Missing required `count` column for indicator analysis",
+                Some(&error.to_string()),
+                "none",
+                "GET",
+            );
+            return Err(error);
+        }
+    };
+
+    let weights = match method {
+        IndicatorMethod::Mean => count_weights.clone(),
+        IndicatorMethod::AmountWeighted => match require_column(frame, "deal_amount") {
+            Ok(column) => column,
+            Err(error) => {
+                log_event(
+                    file!(),
+                    "PerformanceMetrics",
+                    "indicator_analysis",
+                    "metrics.indicator",
+                    line!(),
+                    "Missing `deal_amount` column required for amount-weighted analysis",
+                    Some(&error.to_string()),
+                    "none",
+                    "GET",
+                );
+                return Err(error);
+            }
+        },
+        IndicatorMethod::ValueWeighted => match require_column(frame, "value") {
+            Ok(column) => column,
+            Err(error) => {
+                log_event(
+                    file!(),
+                    "PerformanceMetrics",
+                    "indicator_analysis",
+                    "metrics.indicator",
+                    line!(),
+                    "Missing `value` column required for value-weighted analysis",
+                    Some(&error.to_string()),
+                    "none",
+                    "GET",
+                );
+                return Err(error);
+            }
+        },
+    };
+
+    let ffr_values = require_column(frame, "ffr").inspect_err(|error| {
+        log_event(
+            file!(),
+            "PerformanceMetrics",
+            "indicator_analysis",
+            "metrics.indicator",
+            line!(),
+            "Missing `ffr` column required for indicator analysis",
+            Some(&error.to_string()),
+            "none",
+            "GET",
+        );
+    })?;
+    let pa_values = require_column(frame, "pa").inspect_err(|error| {
+        log_event(
             file!(),
             "PerformanceMetrics",
             "indicator_analysis",
@@ -610,127 +1356,1274 @@ pub fn indicator_analysis(frame: &DataFrame, method: IndicatorMethod) -> Metrics
         log_event(
             file!(),
             "PerformanceMetrics",
-            "indicator_analysis",
-            "metrics.indicator",
+            "indicator_analysis",
+            "metrics.indicator",
+            line!(),
+            "Missing `pos` column required for indicator analysis",
+            Some(&error.to_string()),
+            "none",
+            "GET",
+        );
+    })?;
+
+    let (ffr, ffr_weight) = weighted_average(&ffr_values, &weights, method, "ffr")?;
+    let (pa, pa_weight) = weighted_average(&pa_values, &weights, method, "pa")?;
+    let (pos, pos_weight) =
+        weighted_average(&pos_values, &count_weights, IndicatorMethod::Mean, "pos")?;
+
+    let indicators = Series::new("indicator", &["ffr", "pa", "pos"]);
+    let values = Series::new("value", &[ffr, pa, pos]);
+    let weight_sums = Series::new("weight_sum", &[ffr_weight, pa_weight, pos_weight]);
+    let result = DataFrame::new(vec![indicators, values, weight_sums])?;
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "indicator_analysis",
+        "metrics.indicator",
+        line!(),
+        &format!("Computed indicator analysis using {:?} weighting", method),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(result)
+}
+
+/// [`indicator_analysis`] evaluated independently within each value of `group_column`.
+///
+/// Groups are emitted in ascending order, three rows apiece, as a long frame of
+/// `group_column`, `indicator`, `value` and `weight_sum`. A group without usable weights
+/// fails with [`MetricsError::ZeroWeights`] just like the ungrouped analysis.
+pub fn indicator_analysis_grouped(
+    frame: &DataFrame,
+    group_column: &str,
+    method: IndicatorMethod,
+) -> MetricsResult<DataFrame> {
+    let group_dtype = frame
+        .column(group_column)
+        .map(|series| series.dtype().clone())
+        .map_err(|_| {
+            log_event(
+                file!(),
+                "PerformanceMetrics",
+                "indicator_analysis_grouped",
+                "metrics.indicator",
+                line!(),
+                &format!("Missing group column `{group_column}` for indicator analysis"),
+                None,
+                "none",
+                "GET",
+            );
+            MetricsError::MissingColumn(group_column.to_string())
+        })?;
+
+    let sorted = frame
+        .clone()
+        .lazy()
+        .sort(group_column, SortOptions::default())
+        .collect()?;
+    let mut result = DataFrame::new(vec![
+        Series::new_empty(group_column, &group_dtype),
+        Series::new_empty("indicator", &DataType::Utf8),
+        Series::new_empty("value", &DataType::Float64),
+        Series::new_empty("weight_sum", &DataType::Float64),
+    ])?;
+    let groups = sorted.partition_by_stable([group_column], true)?;
+    for group in &groups {
+        let indicators = indicator_analysis(group, method)?;
+        let key = group
+            .column(group_column)?
+            .new_from_index(0, indicators.height());
+        let mut rows = DataFrame::new(vec![key])?;
+        rows.hstack_mut(indicators.get_columns())?;
+        result.vstack_mut(&rows)?;
+    }
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "indicator_analysis_grouped",
+        "metrics.indicator",
+        line!(),
+        &format!(
+            "Computed indicator analysis for {} `{group_column}` groups using {:?} weighting",
+            groups.len(),
+            method
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(result)
+}
+
+pub fn indicator_analysis_with_method(frame: &DataFrame, method: &str) -> MetricsResult<DataFrame> {
+    match IndicatorMethod::from_str(method) {
+        Ok(parsed) => indicator_analysis(frame, parsed),
+        Err(error) => {
+            log_event(
+                file!(),
+                "PerformanceMetrics",
+                "indicator_analysis_with_method",
+                "metrics.indicator",
+                line!(),
+                &format!("Invalid indicator analysis method `{method}`"),
+                Some(&error.to_string()),
+                "none",
+                "GET",
+            );
+            Err(error)
+        }
+    }
+}
+
+/// Portfolio turnover per timestamp: the sum of absolute weight changes versus each
+/// symbol's previous row.
+///
+/// Rows are ordered by `time_column`, and a symbol's first appearance counts its full
+/// weight, so the first timestamp reports the gross initial allocation. Liquidated
+/// positions should be kept as zero-weight rows to register as turnover. Returns a frame
+/// with `time_column` and `turnover`.
+pub fn turnover(
+    weights: &DataFrame,
+    time_column: &str,
+    symbol_column: &str,
+    weight_column: &str,
+) -> MetricsResult<DataFrame> {
+    let weight = col(weight_column).cast(DataType::Float64);
+    let previous = weight
+        .clone()
+        .shift(1)
+        .over([col(symbol_column)])
+        .fill_null(lit(0.0));
+    let table = weights
+        .clone()
+        .lazy()
+        .sort(time_column, SortOptions::default())
+        .with_column((weight - previous).abs().alias("turnover"))
+        .group_by_stable([col(time_column)])
+        .agg([col("turnover").sum()])
+        .collect()
+        .map_err(|error| {
+            log_event(
+                file!(),
+                "PerformanceMetrics",
+                "turnover",
+                "metrics.portfolio",
+                line!(),
+                "Failed to compute portfolio turnover",
+                Some(&error.to_string()),
+                "none",
+                "GET",
+            );
+            MetricsError::from(error)
+        })?;
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "turnover",
+        "metrics.portfolio",
+        line!(),
+        &format!(
+            "Computed turnover for {} timestamps from `{weight_column}`",
+            table.height()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(table)
+}
+
+/// Advisory check that `returns` spans `start`..`end` without implicit gaps.
+///
+/// Compares the number of returns with [`AnalysisFrequency::expected_periods`] and logs a
+/// warning when they differ by more than [`SERIES_LENGTH_TOLERANCE`] of the expected
+/// count. Returns `true` when the length looks consistent. `start` and `end` are the
+/// timestamps of the first and last return.
+pub fn validate_series_length(
+    returns: &[f64],
+    frequency: AnalysisFrequency,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> bool {
+    let expected = frequency.expected_periods(start, end);
+    let observed = returns.len() as f64;
+    let consistent = (observed - expected).abs() <= expected * SERIES_LENGTH_TOLERANCE;
+
+    if consistent {
+        log_event(
+            file!(),
+            "AnalysisFrequency",
+            "validate_series_length",
+            "metrics.validate",
+            line!(),
+            &format!("Observed {observed} returns against {expected:.2} expected periods"),
+            None,
+            "none",
+            "GET",
+        );
+    } else {
+        log_event_at(
+            Level::WARN,
+            file!(),
+            "AnalysisFrequency",
+            "validate_series_length",
+            "metrics.validate",
+            line!(),
+            &format!(
+                "Observed {observed} returns but expected {expected:.2} {:?} periods between {start} and {end}; annualization may be skewed by gaps",
+                frequency.unit()
+            ),
+            None,
+            "none",
+            "GET",
+        );
+    }
+
+    consistent
+}
+
+/// Calendar table of compounded returns, one row per `(year, month)` bucket.
+///
+/// Rows are sorted by `time_column` first, so buckets come out in chronological order.
+/// Non-finite and null returns are dropped before compounding `prod(1 + r) - 1`; the
+/// result carries `year`, `month` and the compounded `return_column`.
+pub fn monthly_returns_table(
+    frame: &DataFrame,
+    time_column: &str,
+    return_column: &str,
+) -> MetricsResult<DataFrame> {
+    let returns = col(return_column).cast(DataType::Float64);
+    let table = frame
+        .clone()
+        .lazy()
+        .filter(returns.clone().is_finite())
+        .sort(time_column, SortOptions::default())
+        .with_columns([
+            col(time_column).dt().year().alias("year"),
+            col(time_column).dt().month().alias("month"),
+        ])
+        .group_by_stable([col("year"), col("month")])
+        .agg([((returns + lit(1.0)).product() - lit(1.0)).alias(return_column)])
+        .collect()
+        .map_err(|error| {
+            log_event(
+                file!(),
+                "PerformanceMetrics",
+                "monthly_returns_table",
+                "metrics.calendar",
+                line!(),
+                "Failed to aggregate monthly returns",
+                Some(&error.to_string()),
+                "none",
+                "GET",
+            );
+            MetricsError::from(error)
+        })?;
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "monthly_returns_table",
+        "metrics.calendar",
+        line!(),
+        &format!(
+            "Compounded `{return_column}` into {} monthly buckets",
+            table.height()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(table)
+}
+
+pub fn risk_analysis(
+    returns: &[f64],
+    periods_per_year: Option<f64>,
+    freq: Option<&str>,
+    mode: Option<&str>,
+) -> MetricsResult<DataFrame> {
+    risk_analysis_with_benchmark(returns, None, periods_per_year, freq, mode)
+}
+
+/// [`risk_analysis`] whose `information_ratio` row, when a benchmark is supplied, measures
+/// the active returns `returns - benchmark`. The `sharpe` row stays absolute.
+pub fn risk_analysis_with_benchmark(
+    returns: &[f64],
+    benchmark: Option<&[f64]>,
+    periods_per_year: Option<f64>,
+    freq: Option<&str>,
+    mode: Option<&str>,
+) -> MetricsResult<DataFrame> {
+    let accumulation_mode = match mode {
+        Some(mode_str) => match AccumulationMode::from_str(mode_str) {
+            Ok(mode) => mode,
+            Err(error) => {
+                log_event(
+                    file!(),
+                    "PerformanceMetrics",
+                    "risk_analysis_with_benchmark",
+                    "metrics.evaluate",
+                    line!(),
+                    &format!("Invalid accumulation mode `{mode_str}`"),
+                    Some(&error.to_string()),
+                    "none",
+                    "GET",
+                );
+                return Err(error);
+            }
+        },
+        None => {
+            log_event(
+                file!(),
+                "PerformanceMetrics",
+                "risk_analysis_with_benchmark",
+                "metrics.evaluate",
+                line!(),
+                "No accumulation mode supplied; defaulting to arithmetic accumulation",
+                None,
+                "none",
+                "GET",
+            );
+            AccumulationMode::default()
+        }
+    };
+
+    let active = benchmark
+        .map(|benchmark| active_returns(returns, benchmark, "risk_analysis_with_benchmark"))
+        .transpose()?;
+
+    let mut metrics = PerformanceMetrics::evaluate_with_scaler_or_frequency(
+        returns,
+        periods_per_year,
+        freq,
+        accumulation_mode,
+    )?;
+    if let Some(active) = &active {
+        metrics.information_ratio = PerformanceMetrics::evaluate_with_scaler_or_frequency(
+            active,
+            periods_per_year,
+            freq,
+            accumulation_mode,
+        )?
+        .information_ratio;
+    }
+    let frame = metrics.to_risk_dataframe()?;
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "risk_analysis_with_benchmark",
+        "metrics.evaluate",
+        line!(),
+        &format!(
+            "Evaluated risk metrics using {:?} accumulation with scaler {:?} and frequency {:?} (benchmark supplied: {})",
+            accumulation_mode,
+            periods_per_year,
+            freq,
+            active.is_some()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(frame)
+}
+
+/// Risk analysis of active returns, i.e. `returns - benchmark` period by period.
+///
+/// Both series must have the same length. The `information_ratio` row then measures active
+/// return per unit of tracking error.
+pub fn risk_analysis_relative(
+    returns: &[f64],
+    benchmark: &[f64],
+    periods_per_year: Option<f64>,
+    freq: Option<&str>,
+    mode: Option<&str>,
+) -> MetricsResult<DataFrame> {
+    let active = active_returns(returns, benchmark, "risk_analysis_relative")?;
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "risk_analysis_relative",
+        "metrics.evaluate",
+        line!(),
+        &format!("Computed {} active returns against benchmark", active.len()),
+        None,
+        "none",
+        "GET",
+    );
+
+    risk_analysis(&active, periods_per_year, freq, mode)
+}
+
+/// CAPM beta and annualized alpha of `returns` against `benchmark`.
+///
+/// Beta is the sample covariance over the benchmark's sample variance; alpha is the
+/// per-period intercept `mean(returns) - beta * mean(benchmark)` scaled by
+/// `periods_per_year`. Pairs with a non-finite value on either side are dropped, and beta
+/// is 0.0 when the benchmark has no variance.
+pub fn beta_alpha(
+    returns: &[f64],
+    benchmark: &[f64],
+    periods_per_year: f64,
+) -> MetricsResult<(f64, f64)> {
+    if returns.len() != benchmark.len() {
+        let error = MetricsError::LengthMismatch {
+            returns: returns.len(),
+            benchmark: benchmark.len(),
+        };
+        log_event(
+            file!(),
+            "PerformanceMetrics",
+            "beta_alpha",
+            "metrics.evaluate",
+            line!(),
+            "Cannot regress series of different lengths",
+            Some(&error.to_string()),
+            "none",
+            "GET",
+        );
+        return Err(error);
+    }
+
+    let (clean_returns, clean_benchmark) = sanitize_pairs(returns, benchmark);
+    let (beta, alpha) = if clean_returns.is_empty() {
+        (0.0, 0.0)
+    } else {
+        let mean_return = clean_returns.iter().sum::<f64>() / clean_returns.len() as f64;
+        let mean_benchmark = clean_benchmark.iter().sum::<f64>() / clean_benchmark.len() as f64;
+        let benchmark_variance = sample_variance(&clean_benchmark, mean_benchmark);
+        let beta = if benchmark_variance > f64::EPSILON {
+            sample_covariance(
+                &clean_returns,
+                &clean_benchmark,
+                mean_return,
+                mean_benchmark,
+            ) / benchmark_variance
+        } else {
+            0.0
+        };
+        (
+            beta,
+            (mean_return - beta * mean_benchmark) * periods_per_year,
+        )
+    };
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "beta_alpha",
+        "metrics.evaluate",
+        line!(),
+        &format!(
+            "Estimated beta {beta:.6} and alpha {alpha:.6} from {} paired observations",
+            clean_returns.len()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok((beta, alpha))
+}
+
+/// Evaluate [`PerformanceMetrics`] over every trailing window of `window` returns.
+///
+/// Element `i` covers `returns[i..i + window]`, so the output has
+/// `returns.len() - window + 1` entries (none when the series is shorter than the window).
+/// A zero `window` fails with [`MetricsError::InvalidWindow`].
+pub fn rolling_metrics(
+    returns: &[f64],
+    window: usize,
+    periods_per_year: f64,
+    mode: AccumulationMode,
+) -> MetricsResult<Vec<PerformanceMetrics>> {
+    ensure_window(window, "rolling_metrics")?;
+
+    let metrics: Vec<PerformanceMetrics> = returns
+        .par_windows(window)
+        .map(|slice| PerformanceMetrics::evaluate_with_mode(slice, periods_per_year, mode))
+        .collect();
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "rolling_metrics",
+        "metrics.evaluate",
+        line!(),
+        &format!(
+            "Evaluated {} rolling windows of {window} periods using {:?} mode",
+            metrics.len(),
+            mode
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(metrics)
+}
+
+/// Maximum drawdown within every trailing window of `window` returns.
+///
+/// Windows line up with [`rolling_metrics`], giving `returns.len() - window + 1` values,
+/// and each restarts its equity curve so only losses inside the window count. Non-finite
+/// returns are dropped within each window. A zero `window` fails with
+/// [`MetricsError::InvalidWindow`].
+pub fn rolling_max_drawdown(
+    returns: &[f64],
+    window: usize,
+    mode: AccumulationMode,
+) -> MetricsResult<Vec<f64>> {
+    ensure_window(window, "rolling_max_drawdown")?;
+
+    let drawdowns: Vec<f64> = returns
+        .par_windows(window)
+        .map(|slice| {
+            let (clean_returns, _) = sanitize_returns(slice);
+            deepest_drawdown(&drawdowns(&equity_curve(&clean_returns, mode), mode))
+        })
+        .collect();
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "rolling_max_drawdown",
+        "metrics.drawdown",
+        line!(),
+        &format!(
+            "Computed max drawdown over {} rolling windows of {window} periods using {:?} mode",
+            drawdowns.len(),
+            mode
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(drawdowns)
+}
+
+/// Per-period drawdown of the equity curve implied by `returns`.
+///
+/// Sum mode reports `running_sum - running_peak`, product mode `(value / peak) - 1.0`,
+/// matching the peak tracking behind [`PerformanceMetrics::max_drawdown`]. Non-finite
+/// returns are filtered first, so the output may be shorter than the input.
+pub fn drawdown_series(returns: &[f64], mode: AccumulationMode) -> Vec<f64> {
+    let (clean_returns, filtered_out) = sanitize_returns(returns);
+    let series = drawdowns(&equity_curve(&clean_returns, mode), mode);
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "drawdown_series",
+        "metrics.drawdown",
+        line!(),
+        &format!(
+            "Computed {} drawdown points using {:?} mode (filtered {filtered_out} non-finite returns)",
+            series.len(),
+            mode
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    series
+}
+
+/// Tabular companion to [`drawdown_series`] with `period`, `equity`, `peak` and `drawdown`
+/// columns, one row per finite return.
+///
+/// `equity` is the running sum (sum mode) or growth of one unit (product mode) and `peak`
+/// its running maximum under the same convention as [`PerformanceMetrics::max_drawdown`].
+pub fn drawdown_analysis(returns: &[f64], mode: AccumulationMode) -> MetricsResult<DataFrame> {
+    let (clean_returns, filtered_out) = sanitize_returns(returns);
+    let curve = equity_curve(&clean_returns, mode);
+    let underwater = drawdowns(&curve, mode);
+    let mut peak = match mode {
+        AccumulationMode::Sum => 0.0,
+        AccumulationMode::Product => curve.first().copied().unwrap_or(1.0),
+    };
+    let peaks: Vec<f64> = curve
+        .iter()
+        .map(|value| {
+            peak = peak.max(*value);
+            peak
+        })
+        .collect();
+    let periods: Vec<u32> = (0..curve.len() as u32).collect();
+
+    let frame = DataFrame::new(vec![
+        Series::new("period", periods),
+        Series::new("equity", curve),
+        Series::new("peak", peaks),
+        Series::new("drawdown", underwater),
+    ])?;
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "drawdown_analysis",
+        "metrics.drawdown",
+        line!(),
+        &format!(
+            "Built {} drawdown rows using {:?} mode (filtered {filtered_out} non-finite returns)",
+            frame.height(),
+            mode
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(frame)
+}
+
+/// Ulcer Index: root mean square of the per-period drawdowns from [`drawdown_series`].
+///
+/// Deep and long drawdowns both raise the index; a curve that never falls below its
+/// running peak scores 0.0. Non-finite returns are filtered first.
+pub fn ulcer_index(returns: &[f64], mode: AccumulationMode) -> f64 {
+    let (clean_returns, _) = sanitize_returns(returns);
+    let ulcer = ulcer_index_of(&equity_curve(&clean_returns, mode), mode);
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "ulcer_index",
+        "metrics.drawdown",
+        line!(),
+        &format!(
+            "Computed Ulcer Index {ulcer:.6} from {} returns using {:?} mode",
+            clean_returns.len(),
+            mode
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    ulcer
+}
+
+/// Martin ratio: annualized return divided by the [`ulcer_index`].
+///
+/// Returns 0.0 when the Ulcer Index is zero, i.e. the series never draws down.
+pub fn martin_ratio(returns: &[f64], periods_per_year: f64, mode: AccumulationMode) -> f64 {
+    let (metrics, curve) = PerformanceMetrics::evaluate_with_curve(returns, periods_per_year, mode);
+    let ulcer = ulcer_index_of(&curve, mode);
+    let martin = if ulcer > f64::EPSILON {
+        metrics.annualized_return / ulcer
+    } else {
+        0.0
+    };
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "martin_ratio",
+        "metrics.drawdown",
+        line!(),
+        &format!("Computed Martin ratio {martin:.6} with Ulcer Index {ulcer:.6}"),
+        None,
+        "none",
+        "GET",
+    );
+
+    martin
+}
+
+/// Historical Value at Risk: the `1 - confidence` quantile of the finite returns.
+///
+/// The quantile is linearly interpolated between neighbouring observations and reported
+/// as a return (typically negative). `confidence` is clamped into `(0.0, 1.0)` and empty
+/// input yields 0.0.
+pub fn value_at_risk(returns: &[f64], confidence: f64) -> f64 {
+    let (sorted, tail) = sorted_returns_with_tail(returns, confidence);
+    if sorted.is_empty() {
+        return 0.0;
+    }
+
+    let var = quantile_sorted(&sorted, tail);
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "value_at_risk",
+        "metrics.risk",
+        line!(),
+        &format!(
+            "Computed historical VaR at {:.4} confidence over {} returns",
+            1.0 - tail,
+            sorted.len()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    var
+}
+
+/// Tail ratio: the `1 - tail` quantile of the finite returns divided by the absolute value
+/// of the `tail` quantile, e.g. the 95th over the 5th percentile for `tail = 0.05`.
+///
+/// Quantiles are interpolated like [`value_at_risk`]. Returns 0.0 for empty input or when
+/// the left tail is zero, and [`MetricsError::InvalidTail`] unless `0 < tail < 0.5`.
+pub fn tail_ratio(returns: &[f64], tail: f64) -> MetricsResult<f64> {
+    if !(tail > 0.0 && tail < 0.5) {
+        let error = MetricsError::InvalidTail(tail);
+        log_event(
+            file!(),
+            "PerformanceMetrics",
+            "tail_ratio",
+            "metrics.risk",
+            line!(),
+            "Rejected a tail quantile outside (0, 0.5)",
+            Some(&error.to_string()),
+            "none",
+            "GET",
+        );
+        return Err(error);
+    }
+
+    let (mut sorted, _) = sanitize_returns(returns);
+    sorted.sort_by(f64::total_cmp);
+    if sorted.is_empty() {
+        return Ok(0.0);
+    }
+
+    let right = quantile_sorted(&sorted, 1.0 - tail);
+    let left = quantile_sorted(&sorted, tail).abs();
+    let ratio = if left > f64::EPSILON {
+        right / left
+    } else {
+        0.0
+    };
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "tail_ratio",
+        "metrics.risk",
+        line!(),
+        &format!(
+            "Computed tail ratio {ratio:.6} at tail {tail} over {} returns",
+            sorted.len()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(ratio)
+}
+
+/// Historical Conditional VaR (expected shortfall): the mean of the finite returns at or
+/// below [`value_at_risk`] for the same confidence. Empty input yields 0.0.
+pub fn conditional_value_at_risk(returns: &[f64], confidence: f64) -> f64 {
+    let (sorted, tail) = sorted_returns_with_tail(returns, confidence);
+    if sorted.is_empty() {
+        return 0.0;
+    }
+
+    let var = quantile_sorted(&sorted, tail);
+    let shortfall: Vec<f64> = sorted.iter().copied().take_while(|r| *r <= var).collect();
+    let cvar = if shortfall.is_empty() {
+        var
+    } else {
+        shortfall.iter().sum::<f64>() / shortfall.len() as f64
+    };
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "conditional_value_at_risk",
+        "metrics.risk",
+        line!(),
+        &format!(
+            "Computed historical CVaR at {:.4} confidence from {} tail returns",
+            1.0 - tail,
+            shortfall.len()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    cvar
+}
+
+/// Information coefficient: correlation between model `predictions` and the realized
+/// `returns` they target.
+///
+/// Pairs where either side is non-finite are dropped. Returns 0.0 when fewer than two pairs
+/// remain or either side is constant.
+pub fn information_coefficient(
+    predictions: &[f64],
+    returns: &[f64],
+    method: ICMethod,
+) -> MetricsResult<f64> {
+    if predictions.len() != returns.len() {
+        let error = MetricsError::PredictionLengthMismatch {
+            predictions: predictions.len(),
+            returns: returns.len(),
+        };
+        log_event(
+            file!(),
+            "PerformanceMetrics",
+            "information_coefficient",
+            "metrics.factor",
             line!(),
-            "Missing `pos` column required for indicator analysis",
+            "Cannot correlate series of different lengths",
             Some(&error.to_string()),
             "none",
             "GET",
         );
-    })?;
-
-    let ffr = weighted_average(&ffr_values, &weights, method, "ffr")?;
-    let pa = weighted_average(&pa_values, &weights, method, "pa")?;
-    let pos = weighted_average(&pos_values, &count_weights, IndicatorMethod::Mean, "pos")?;
+        return Err(error);
+    }
 
-    let indicators = Series::new("indicator", &["ffr", "pa", "pos"]);
-    let values = Series::new("value", &[ffr, pa, pos]);
-    let result = DataFrame::new(vec![indicators, values])?;
+    let (clean_predictions, clean_returns) = sanitize_pairs(predictions, returns);
+    let ic = match method {
+        ICMethod::Pearson => pearson_correlation(&clean_predictions, &clean_returns),
+        ICMethod::Spearman => pearson_correlation(
+            &average_ranks(&clean_predictions),
+            &average_ranks(&clean_returns),
+        ),
+    };
 
     log_event(
         file!(),
         "PerformanceMetrics",
-        "indicator_analysis",
-        "metrics.indicator",
+        "information_coefficient",
+        "metrics.factor",
         line!(),
-        &format!("Computed indicator analysis using {:?} weighting", method),
+        &format!(
+            "Computed {method:?} IC {ic:.6} over {} pairs",
+            clean_predictions.len()
+        ),
         None,
         "none",
         "GET",
     );
 
-    Ok(result)
+    Ok(ic)
 }
 
-pub fn indicator_analysis_with_method(frame: &DataFrame, method: &str) -> MetricsResult<DataFrame> {
-    match IndicatorMethod::from_str(method) {
-        Ok(parsed) => indicator_analysis(frame, parsed),
-        Err(error) => {
+/// Per-date [`information_coefficient`] between `prediction_column` and `return_column`.
+///
+/// Dates are emitted in ascending order as a frame of `date_column` and `ic`. Dates with
+/// fewer than two finite prediction/return pairs are skipped and logged.
+pub fn ic_series(
+    frame: &DataFrame,
+    date_column: &str,
+    prediction_column: &str,
+    return_column: &str,
+    method: ICMethod,
+) -> MetricsResult<DataFrame> {
+    let date_dtype = frame
+        .column(date_column)
+        .map(|series| series.dtype().clone())
+        .map_err(|_| MetricsError::MissingColumn(date_column.to_string()))?;
+    require_column(frame, prediction_column)?;
+    require_column(frame, return_column)?;
+
+    let sorted = frame
+        .clone()
+        .lazy()
+        .sort(date_column, SortOptions::default())
+        .collect()?;
+    let mut dates = Series::new_empty(date_column, &date_dtype);
+    let mut values = Vec::new();
+    let mut skipped = 0;
+    for group in sorted.partition_by_stable([date_column], true)? {
+        let predictions: Vec<f64> = require_column(&group, prediction_column)?
+            .into_iter()
+            .map(|value| value.unwrap_or(f64::NAN))
+            .collect();
+        let returns: Vec<f64> = require_column(&group, return_column)?
+            .into_iter()
+            .map(|value| value.unwrap_or(f64::NAN))
+            .collect();
+        let date = group.column(date_column)?.head(Some(1));
+
+        let (valid, _) = sanitize_pairs(&predictions, &returns);
+        if valid.len() < 2 {
+            skipped += 1;
             log_event(
                 file!(),
                 "PerformanceMetrics",
-                "indicator_analysis_with_method",
-                "metrics.indicator",
+                "ic_series",
+                "metrics.factor",
                 line!(),
-                &format!("Invalid indicator analysis method `{method}`"),
-                Some(&error.to_string()),
+                &format!(
+                    "Skipping {} with {} valid pairs",
+                    date.get(0)
+                        .map(|value| value.to_string())
+                        .unwrap_or_default(),
+                    valid.len()
+                ),
+                None,
                 "none",
                 "GET",
             );
-            Err(error)
+            continue;
         }
+
+        values.push(information_coefficient(&predictions, &returns, method)?);
+        dates.append(&date)?;
     }
+
+    let series = DataFrame::new(vec![dates, Series::new("ic", values)])?;
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "ic_series",
+        "metrics.factor",
+        line!(),
+        &format!(
+            "Computed {method:?} IC for {} dates ({skipped} skipped)",
+            series.height()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(series)
 }
 
-pub fn risk_analysis(
-    returns: &[f64],
-    periods_per_year: Option<f64>,
-    freq: Option<&str>,
-    mode: Option<&str>,
-) -> MetricsResult<DataFrame> {
-    let accumulation_mode = match mode {
-        Some(mode_str) => match AccumulationMode::from_str(mode_str) {
-            Ok(mode) => mode,
-            Err(error) => {
-                log_event(
-                    file!(),
-                    "PerformanceMetrics",
-                    "risk_analysis",
-                    "metrics.evaluate",
-                    line!(),
-                    &format!("Invalid accumulation mode `{mode_str}`"),
-                    Some(&error.to_string()),
-                    "none",
-                    "GET",
-                );
-                return Err(error);
-            }
-        },
-        None => {
+/// Square matrix of pairwise Pearson correlations between `columns`.
+///
+/// The result has one `Float64` column per input and its rows follow the same order, so cell
+/// `(i, j)` is the correlation of `columns[i]` with `columns[j]`. Each cell only uses rows
+/// where both columns are finite. The diagonal is 1.0; a column with zero variance correlates
+/// 0.0 with every other column and is logged.
+pub fn correlation_matrix(frame: &DataFrame, columns: &[&str]) -> MetricsResult<DataFrame> {
+    let values = columns
+        .iter()
+        .map(|name| column_values(frame, name))
+        .collect::<MetricsResult<Vec<_>>>()?;
+
+    for (name, column) in columns.iter().zip(&values) {
+        let (clean, _) = sanitize_returns(column);
+        let mean = clean.iter().sum::<f64>() / clean.len().max(1) as f64;
+        if sample_variance(&clean, mean) <= f64::EPSILON {
             log_event(
                 file!(),
                 "PerformanceMetrics",
-                "risk_analysis",
-                "metrics.evaluate",
+                "correlation_matrix",
+                "metrics.portfolio",
                 line!(),
-                "No accumulation mode supplied; defaulting to arithmetic accumulation",
+                &format!("Column `{name}` has zero variance; its correlations are 0.0"),
                 None,
                 "none",
                 "GET",
             );
-            AccumulationMode::default()
         }
+    }
+
+    let matrix = pairwise_matrix(columns, &values, |i, j, left, right| {
+        if i == j {
+            1.0
+        } else {
+            pearson_correlation(left, right)
+        }
+    })?;
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "correlation_matrix",
+        "metrics.portfolio",
+        line!(),
+        &format!("Computed {0}x{0} correlation matrix", columns.len()),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(matrix)
+}
+
+/// Square matrix of annualized sample covariances between `columns`.
+///
+/// Laid out like [`correlation_matrix`]: each cell is the `n - 1` covariance over the rows
+/// where both columns are finite, scaled by `periods_per_year`, so the diagonal holds each
+/// column's annualized variance.
+pub fn covariance_matrix(
+    frame: &DataFrame,
+    columns: &[&str],
+    periods_per_year: f64,
+) -> MetricsResult<DataFrame> {
+    let values = columns
+        .iter()
+        .map(|name| column_values(frame, name))
+        .collect::<MetricsResult<Vec<_>>>()?;
+
+    let matrix = pairwise_matrix(columns, &values, |_, _, left, right| {
+        let left_mean = left.iter().sum::<f64>() / left.len().max(1) as f64;
+        let right_mean = right.iter().sum::<f64>() / right.len().max(1) as f64;
+        sample_covariance(left, right, left_mean, right_mean) * periods_per_year
+    })?;
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "covariance_matrix",
+        "metrics.portfolio",
+        line!(),
+        &format!(
+            "Computed {0}x{0} covariance matrix at {periods_per_year} periods per year",
+            columns.len()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    Ok(matrix)
+}
+
+/// Annualized Sortino ratio: mean return over the downside deviation, scaled by
+/// `sqrt(periods_per_year)`.
+///
+/// The downside deviation is the root mean square of the negative returns taken over every
+/// finite observation. Returns 0.0 when there is no downside.
+pub fn sortino_ratio(returns: &[f64], periods_per_year: f64) -> f64 {
+    let (clean_returns, _) = sanitize_returns(returns);
+    let downside = downside_deviation_of(&clean_returns, 0.0);
+    let sortino = if downside > f64::EPSILON {
+        let mean = clean_returns.iter().sum::<f64>() / clean_returns.len() as f64;
+        mean / downside * periods_per_year.sqrt()
+    } else {
+        0.0
     };
 
-    let metrics = PerformanceMetrics::evaluate_with_scaler_or_frequency(
-        returns,
-        periods_per_year,
-        freq,
-        accumulation_mode,
-    )?;
-    let frame = metrics.to_risk_dataframe()?;
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "sortino_ratio",
+        "metrics.risk",
+        line!(),
+        &format!(
+            "Computed Sortino ratio {sortino:.6} from {} returns",
+            clean_returns.len()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    sortino
+}
+
+/// Annualized downside deviation below a minimum acceptable return `mar`.
+///
+/// Shortfalls `min(r - mar, 0)` are squared and averaged over every finite observation,
+/// the same convention [`sortino_ratio`] uses, then scaled by `sqrt(periods_per_year)`.
+/// Returns 0.0 when no return falls below `mar`.
+pub fn downside_deviation(returns: &[f64], mar: f64, periods_per_year: f64) -> f64 {
+    let (clean_returns, _) = sanitize_returns(returns);
+    let deviation = downside_deviation_of(&clean_returns, mar) * periods_per_year.sqrt();
 
     log_event(
         file!(),
         "PerformanceMetrics",
-        "risk_analysis",
-        "metrics.evaluate",
+        "downside_deviation",
+        "metrics.risk",
         line!(),
         &format!(
-            "Evaluated risk metrics using {:?} accumulation with scaler {:?} and frequency {:?}",
-            accumulation_mode, periods_per_year, freq
+            "Computed downside deviation {deviation:.6} below {mar} from {} returns",
+            clean_returns.len()
         ),
         None,
         "none",
         "GET",
     );
 
-    Ok(frame)
+    deviation
+}
+
+/// Omega ratio: total gain above `threshold` divided by total shortfall below it.
+///
+/// Non-finite returns are ignored. Returns 0.0 when no observation falls below the
+/// threshold.
+pub fn omega_ratio(returns: &[f64], threshold: f64) -> f64 {
+    let (clean_returns, _) = sanitize_returns(returns);
+    let (gains, losses) = clean_returns
+        .iter()
+        .fold((0.0, 0.0), |(gains, losses), value| {
+            let excess = value - threshold;
+            if excess > 0.0 {
+                (gains + excess, losses)
+            } else {
+                (gains, losses - excess)
+            }
+        });
+    let omega = if losses > f64::EPSILON {
+        gains / losses
+    } else {
+        0.0
+    };
+
+    log_event(
+        file!(),
+        "PerformanceMetrics",
+        "omega_ratio",
+        "metrics.risk",
+        line!(),
+        &format!(
+            "Computed Omega ratio {omega:.6} at threshold {threshold} from {} returns",
+            clean_returns.len()
+        ),
+        None,
+        "none",
+        "GET",
+    );
+
+    omega
+}
+
+fn downside_deviation_of(clean_returns: &[f64], mar: f64) -> f64 {
+    if clean_returns.is_empty() {
+        return 0.0;
+    }
+
+    let squares = clean_returns
+        .iter()
+        .map(|value| (value - mar).min(0.0).powi(2))
+        .sum::<f64>();
+    (squares / clean_returns.len() as f64).sqrt()
+}
+
+fn sorted_returns_with_tail(returns: &[f64], confidence: f64) -> (Vec<f64>, f64) {
+    let (mut sorted, _) = sanitize_returns(returns);
+    sorted.sort_by(f64::total_cmp);
+    let confidence = confidence.clamp(f64::EPSILON, 1.0 - f64::EPSILON);
+    (sorted, 1.0 - confidence)
+}
+
+/// Linearly interpolated quantile of an ascending, non-empty slice.
+pub(crate) fn quantile_sorted(sorted: &[f64], quantile: f64) -> f64 {
+    let position = quantile.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f64;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * fraction
+}
+
+fn equity_curve(returns: &[f64], mode: AccumulationMode) -> Vec<f64> {
+    let mut curve = Vec::with_capacity(returns.len());
+    match mode {
+        AccumulationMode::Sum => {
+            let mut running_sum = 0.0;
+            for value in returns {
+                running_sum += value;
+                curve.push(running_sum);
+            }
+        }
+        AccumulationMode::Product => {
+            let mut cumulative_product = 1.0;
+            for value in returns {
+                cumulative_product *= 1.0 + value;
+                curve.push(cumulative_product);
+            }
+        }
+    }
+    curve
+}
+
+fn drawdowns(curve: &[f64], mode: AccumulationMode) -> Vec<f64> {
+    match mode {
+        AccumulationMode::Sum => {
+            let mut running_peak = 0.0;
+            curve
+                .iter()
+                .map(|value| {
+                    if *value > running_peak {
+                        running_peak = *value;
+                    }
+                    value - running_peak
+                })
+                .collect()
+        }
+        AccumulationMode::Product => {
+            let mut peak = curve.first().copied().unwrap_or(1.0);
+            curve
+                .iter()
+                .map(|value| {
+                    if *value > peak {
+                        peak = *value;
+                    }
+                    (value / peak) - 1.0
+                })
+                .collect()
+        }
+    }
+}
+
+fn ulcer_index_of(curve: &[f64], mode: AccumulationMode) -> f64 {
+    if curve.is_empty() {
+        return 0.0;
+    }
+
+    let squares = drawdowns(curve, mode)
+        .iter()
+        .map(|value| value.powi(2))
+        .sum::<f64>();
+    (squares / curve.len() as f64).sqrt()
+}
+
+fn deepest_drawdown(drawdowns: &[f64]) -> f64 {
+    drawdowns.iter().copied().fold(0.0, f64::min)
+}
+
+/// Counts the longest run of periods below the running peak. A drawdown that never
+/// recovers is counted through the last observation.
+fn longest_drawdown_duration(drawdowns: &[f64]) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for drawdown in drawdowns {
+        if *drawdown < 0.0 {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
+
+/// Sample standard deviation from a count, sum and sum of squares; 0.0 below two values.
+fn moment_std(count: usize, sum: f64, sum_squares: f64) -> f64 {
+    if count < 2 {
+        return 0.0;
+    }
+    let n = count as f64;
+    ((sum_squares - sum * sum / n) / (n - 1.0)).max(0.0).sqrt()
 }
 
 fn sample_variance(values: &[f64], mean: f64) -> f64 {
-    if values.len() < 2 {
+    variance_with_ddof(values, mean, 1)
+}
+
+/// Variance dividing by `n - ddof`; 0.0 for a single value or when `n <= ddof`.
+fn variance_with_ddof(values: &[f64], mean: f64, ddof: usize) -> f64 {
+    if values.len() < 2 || values.len() <= ddof {
         return 0.0;
     }
 
@@ -741,7 +2634,32 @@ fn sample_variance(values: &[f64], mean: f64) -> f64 {
             diff * diff
         })
         .sum::<f64>();
-    sum_squares / (values.len() as f64 - 1.0)
+    sum_squares / (values.len() - ddof) as f64
+}
+
+fn sample_covariance(left: &[f64], right: &[f64], left_mean: f64, right_mean: f64) -> f64 {
+    if left.len() < 2 {
+        return 0.0;
+    }
+
+    let sum_products = left
+        .par_iter()
+        .zip(right.par_iter())
+        .map(|(l, r)| (l - left_mean) * (r - right_mean))
+        .sum::<f64>();
+    sum_products / (left.len() as f64 - 1.0)
+}
+
+/// Calmar ratio: annualized return over the magnitude of the maximum drawdown.
+///
+/// Returns 0.0 when there is no drawdown to avoid dividing by zero.
+fn calmar_ratio(annualized_return: f64, max_drawdown: f64) -> f64 {
+    let magnitude = max_drawdown.abs();
+    if magnitude > f64::EPSILON {
+        annualized_return / magnitude
+    } else {
+        0.0
+    }
 }
 
 fn require_column(frame: &DataFrame, name: &str) -> MetricsResult<Float64Chunked> {
@@ -760,6 +2678,26 @@ fn series_to_f64(series: &Series) -> PolarsResult<Float64Chunked> {
     }
 }
 
+/// Fail with [`MetricsError::InvalidWindow`] when `window` is zero.
+fn ensure_window(window: usize, caller: &str) -> MetricsResult<()> {
+    if window == 0 {
+        let error = MetricsError::InvalidWindow(window);
+        log_event(
+            file!(),
+            "PerformanceMetrics",
+            caller,
+            "metrics.evaluate",
+            line!(),
+            "Rejected an empty rolling window",
+            Some(&error.to_string()),
+            "none",
+            "GET",
+        );
+        return Err(error);
+    }
+    Ok(())
+}
+
 fn sanitize_returns(returns: &[f64]) -> (Vec<f64>, usize) {
     let mut cleaned = Vec::with_capacity(returns.len());
     let mut filtered = 0;
@@ -775,16 +2713,142 @@ fn sanitize_returns(returns: &[f64]) -> (Vec<f64>, usize) {
     (cleaned, filtered)
 }
 
+/// Element-wise `returns - benchmark`, failing when the lengths differ.
+fn active_returns(returns: &[f64], benchmark: &[f64], caller: &str) -> MetricsResult<Vec<f64>> {
+    if returns.len() != benchmark.len() {
+        let error = MetricsError::LengthMismatch {
+            returns: returns.len(),
+            benchmark: benchmark.len(),
+        };
+        log_event(
+            file!(),
+            "PerformanceMetrics",
+            caller,
+            "metrics.evaluate",
+            line!(),
+            "Cannot compute active returns from series of different lengths",
+            Some(&error.to_string()),
+            "none",
+            "GET",
+        );
+        return Err(error);
+    }
+
+    Ok(returns
+        .iter()
+        .zip(benchmark)
+        .map(|(value, bench)| value - bench)
+        .collect())
+}
+
+/// Sample Pearson correlation of two equally long series; 0.0 for fewer than two pairs or
+/// when either side has no variance.
+fn pearson_correlation(left: &[f64], right: &[f64]) -> f64 {
+    if left.len() < 2 {
+        return 0.0;
+    }
+
+    let left_mean = left.iter().sum::<f64>() / left.len() as f64;
+    let right_mean = right.iter().sum::<f64>() / right.len() as f64;
+    let denominator =
+        (sample_variance(left, left_mean) * sample_variance(right, right_mean)).sqrt();
+    if denominator > f64::EPSILON {
+        sample_covariance(left, right, left_mean, right_mean) / denominator
+    } else {
+        0.0
+    }
+}
+
+/// One-based ranks of `values`, with tied values sharing the average of their ranks.
+fn average_ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&left, &right| values[left].total_cmp(&values[right]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let average = (start + end + 1) as f64 / 2.0;
+        for &index in &order[start..end] {
+            ranks[index] = average;
+        }
+        start = end;
+    }
+    ranks
+}
+
+/// Values of `name` as `f64`, with nulls mapped to NaN so they line up with other columns.
+fn column_values(frame: &DataFrame, name: &str) -> MetricsResult<Vec<f64>> {
+    Ok(require_column(frame, name)?
+        .into_iter()
+        .map(|value| value.unwrap_or(f64::NAN))
+        .collect())
+}
+
+/// Square frame whose cell `(i, j)` is `cell(i, j, ..)` over the finite pairs of columns `i`
+/// and `j`. Only the upper triangle is computed; the lower one is mirrored.
+fn pairwise_matrix(
+    columns: &[&str],
+    values: &[Vec<f64>],
+    cell: impl Fn(usize, usize, &[f64], &[f64]) -> f64,
+) -> MetricsResult<DataFrame> {
+    let size = columns.len();
+    let mut matrix = vec![vec![0.0; size]; size];
+    for i in 0..size {
+        for j in i..size {
+            let (left, right) = sanitize_pairs(&values[i], &values[j]);
+            let value = cell(i, j, &left, &right);
+            matrix[i][j] = value;
+            matrix[j][i] = value;
+        }
+    }
+
+    let series = columns
+        .iter()
+        .zip(matrix)
+        .map(|(name, column)| Series::new(name, column))
+        .collect();
+    Ok(DataFrame::new(series)?)
+}
+
+/// Keep only the positions where both series hold a finite value.
+fn sanitize_pairs(left: &[f64], right: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    left.iter()
+        .zip(right)
+        .filter(|(l, r)| l.is_finite() && r.is_finite())
+        .map(|(l, r)| (*l, *r))
+        .unzip()
+}
+
+/// Weighted mean of `values` together with the total effective weight behind it.
 fn weighted_average(
     values: &Float64Chunked,
     weights: &Float64Chunked,
     method: IndicatorMethod,
     indicator: &str,
-) -> MetricsResult<f64> {
+) -> MetricsResult<(f64, f64)> {
+    if !values.into_iter().flatten().any(f64::is_finite) {
+        log_event(
+            file!(),
+            "PerformanceMetrics",
+            "weighted_average",
+            "metrics.indicator",
+            line!(),
+            &format!("Indicator {indicator} has only null or non-finite values"),
+            None,
+            "none",
+            "GET",
+        );
+        return Err(MetricsError::AllNullColumn(indicator.to_string()));
+    }
+
     let mut numerator = 0.0;
     let mut denominator = 0.0;
 
-    for (value, weight) in values.into_iter().zip(weights.into_iter()) {
+    for (value, weight) in values.into_iter().zip(weights) {
         if let (Some(v), Some(w)) = (value, weight)
             && v.is_finite()
             && w.is_finite()
@@ -819,5 +2883,5 @@ fn weighted_average(
         return Err(MetricsError::ZeroWeights(method));
     }
 
-    Ok(numerator / denominator)
+    Ok((numerator / denominator, denominator))
 }
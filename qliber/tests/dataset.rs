@@ -0,0 +1,667 @@
+use std::fs::File;
+use std::io::Write;
+
+use chrono::{TimeZone, Utc};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use polars::prelude::*;
+use tempfile::{NamedTempFile, tempdir};
+
+use qliber::dataset::{
+    CalendarFill, CsvOptions, DatasetError, IntervalBounds, KeepStrategy, MarketData, write_parquet,
+};
+use qliber::features::with_daily_returns;
+
+fn write_csv(contents: &str) -> anyhow::Result<NamedTempFile> {
+    let mut file = NamedTempFile::new()?;
+    write!(file, "{contents}")?;
+    Ok(file)
+}
+
+const PRICES_CSV: &str = "timestamp,close,volume\n2024-01-01T00:00:00Z,100,10\n2024-01-02T00:00:00Z,101,12\n2024-01-03T00:00:00Z,102,9\n2024-01-04T00:00:00Z,104,15\n2024-01-05T00:00:00Z,103,11\n";
+
+#[test]
+fn parquet_round_trip_preserves_shape_and_columns() -> anyhow::Result<()> {
+    let csv = write_csv(PRICES_CSV)?;
+    let market = MarketData::from_csv(csv.path())?;
+    let expected = market.collect()?;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("prices.parquet");
+    market.to_parquet(&path)?;
+
+    let reloaded = ParquetReader::new(File::open(&path)?).finish()?;
+    assert_eq!(reloaded.shape(), expected.shape());
+    assert_eq!(reloaded.get_column_names(), expected.get_column_names());
+    assert!(reloaded.frame_equal(&expected));
+
+    let copy_path = dir.path().join("copy.parquet");
+    write_parquet(&expected, &copy_path)?;
+    let copy = ParquetReader::new(File::open(&copy_path)?).finish()?;
+    assert!(copy.frame_equal(&expected));
+
+    Ok(())
+}
+
+#[test]
+fn ndjson_records_feed_daily_returns() -> anyhow::Result<()> {
+    let mut file = NamedTempFile::new()?;
+    writeln!(
+        file,
+        "{{\"timestamp\":\"2024-01-01T00:00:00Z\",\"close\":100}}\n{{\"timestamp\":\"2024-01-02T00:00:00Z\",\"close\":101}}\n{{\"timestamp\":\"2024-01-03T00:00:00Z\",\"close\":102.01}}"
+    )?;
+
+    let market = MarketData::from_ndjson(file.path())?;
+    let frame = market.collect()?;
+    assert_eq!(frame.shape(), (3, 2));
+    assert_eq!(frame.column("timestamp")?.dtype(), &DataType::Utf8);
+
+    let enriched = with_daily_returns(&frame, "close", "return")?;
+    let returns: Vec<f64> = enriched
+        .column("return")?
+        .f64()?
+        .into_no_null_iter()
+        .collect();
+    assert!((returns[1] - 0.01).abs() < 1e-12);
+    assert!((returns[2] - 0.01).abs() < 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn resample_aggregates_hourly_bars_into_daily_bar() -> anyhow::Result<()> {
+    let csv = write_csv(
+        "timestamp,close,volume\n2024-01-01T09:00:00Z,100,10\n2024-01-01T10:00:00Z,101,20\n2024-01-01T11:00:00Z,99,30\n2024-01-01T12:00:00Z,102,40\n",
+    )?;
+
+    let daily = MarketData::from_csv(csv.path())?
+        .resample(
+            "timestamp",
+            "1d",
+            &[col("close").last(), col("volume").sum()],
+        )?
+        .collect()?;
+
+    assert_eq!(daily.height(), 1);
+    assert_eq!(
+        daily
+            .column("close")?
+            .cast(&DataType::Float64)?
+            .f64()?
+            .get(0),
+        Some(102.0)
+    );
+    assert_eq!(
+        daily
+            .column("volume")?
+            .cast(&DataType::Int64)?
+            .i64()?
+            .get(0),
+        Some(100)
+    );
+
+    Ok(())
+}
+
+const MULTI_SYMBOL_CSV: &str = "timestamp,symbol,close\n2024-01-01T00:00:00Z,AAA,10\n2024-01-01T00:00:00Z,BBB,20\n2024-01-02T00:00:00Z,AAA,11\n2024-01-02T00:00:00Z,BBB,19\n2024-01-03T00:00:00Z,AAA,12\n";
+
+#[test]
+fn filter_symbols_keeps_requested_instruments() -> anyhow::Result<()> {
+    let csv = write_csv(MULTI_SYMBOL_CSV)?;
+    let market = MarketData::from_csv(csv.path())?;
+
+    let only_aaa = market.filter_symbols("symbol", &["AAA"])?.collect()?;
+    assert_eq!(only_aaa.height(), 3);
+    assert!(
+        only_aaa
+            .column("symbol")?
+            .utf8()?
+            .into_no_null_iter()
+            .all(|s| s == "AAA")
+    );
+
+    let both = market
+        .filter_symbols("symbol", &["AAA", "BBB"])?
+        .collect()?;
+    assert_eq!(both.height(), 5);
+
+    Ok(())
+}
+
+#[test]
+fn partition_by_symbol_splits_rows_per_instrument() -> anyhow::Result<()> {
+    let csv = write_csv(MULTI_SYMBOL_CSV)?;
+    let partitions = MarketData::from_csv(csv.path())?.partition_by_symbol("symbol")?;
+
+    assert_eq!(partitions.len(), 2);
+    assert_eq!(partitions["AAA"].height(), 3);
+    assert_eq!(partitions["BBB"].height(), 2);
+
+    let with_null = write_csv("symbol,close\nnull,1\nAAA,2\n,3\n")?;
+    assert!(matches!(
+        MarketData::from_csv(with_null.path())?.partition_by_symbol("symbol"),
+        Err(DatasetError::NullKeys { column, nulls: 1 }) if column == "symbol"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn join_asof_attaches_most_recent_prior_quote() -> anyhow::Result<()> {
+    let quotes = write_csv(
+        "timestamp,bid\n2024-01-01T09:00:00Z,99.5\n2024-01-01T09:05:00Z,100.5\n2024-01-01T09:10:00Z,101.5\n",
+    )?;
+    let trades = write_csv(
+        "timestamp,price\n2024-01-01T08:59:00Z,99\n2024-01-01T09:06:00Z,100\n2024-01-01T09:10:00Z,102\n2024-01-01T09:30:00Z,103\n",
+    )?;
+    let quotes = MarketData::from_csv(quotes.path())?;
+    let trades = MarketData::from_csv(trades.path())?;
+
+    let joined = trades.join_asof(&quotes, "timestamp", None)?.collect()?;
+    let bids: Vec<Option<f64>> = joined.column("bid")?.f64()?.into_iter().collect();
+    assert_eq!(bids, vec![None, Some(100.5), Some(101.5), Some(101.5)]);
+
+    let bounded = trades
+        .join_asof(&quotes, "timestamp", Some(chrono::Duration::minutes(5)))?
+        .collect()?;
+    let bids: Vec<Option<f64>> = bounded.column("bid")?.f64()?.into_iter().collect();
+    assert_eq!(bids, vec![None, Some(100.5), Some(101.5), None]);
+
+    assert!(trades.join_asof(&quotes, "missing", None).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn reindex_calendar_inserts_missing_days() -> anyhow::Result<()> {
+    let csv = write_csv(
+        "timestamp,close\n2024-01-01T00:00:00Z,100\n2024-01-02T00:00:00Z,101\n2024-01-05T00:00:00Z,104\n",
+    )?;
+    let market = MarketData::from_csv(csv.path())?;
+
+    let filled = market
+        .reindex_calendar("timestamp", "1d", CalendarFill::Forward)?
+        .collect()?;
+    assert_eq!(filled.height(), 5);
+    let closes: Vec<Option<f64>> = filled
+        .column("close")?
+        .cast(&DataType::Float64)?
+        .f64()?
+        .into_iter()
+        .collect();
+    assert_eq!(
+        closes,
+        vec![
+            Some(100.0),
+            Some(101.0),
+            Some(101.0),
+            Some(101.0),
+            Some(104.0)
+        ]
+    );
+
+    let sparse = market
+        .reindex_calendar("timestamp", "1d", CalendarFill::Null)?
+        .collect()?;
+    assert_eq!(sparse.height(), 5);
+    assert_eq!(sparse.column("close")?.null_count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn from_dataframe_runs_through_pipeline() -> anyhow::Result<()> {
+    let frame = df!(
+        "symbol" => &["AAA", "AAA", "AAA", "BBB"],
+        "close" => &[100.0, 101.0, 102.01, 50.0]
+    )?;
+    let market = MarketData::from_dataframe(frame);
+
+    let aaa = market
+        .filter_symbols("symbol", &["AAA"])?
+        .select_columns(&["close"])?
+        .collect()?;
+    let enriched = with_daily_returns(&aaa, "close", "return")?;
+    let returns: Vec<f64> = enriched
+        .column("return")?
+        .f64()?
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(returns.len(), 3);
+    assert!((returns[1] - 0.01).abs() < 1e-12);
+    assert!((returns[2] - 0.01).abs() < 1e-12);
+
+    let from_lazy = MarketData::from_lazy(market.lazy()).collect()?;
+    assert_eq!(from_lazy.shape(), (4, 2));
+
+    Ok(())
+}
+
+#[test]
+fn csv_options_read_semicolon_delimited_files() -> anyhow::Result<()> {
+    let csv = write_csv("timestamp;close\n2024-01-01T00:00:00Z;100.5\n2024-01-02T00:00:00Z;101\n")?;
+    let options = CsvOptions {
+        delimiter: b';',
+        ..CsvOptions::default()
+    };
+
+    let frame = MarketData::from_csv_with_options(csv.path(), &options)?.collect()?;
+    assert_eq!(frame.shape(), (2, 2));
+    assert_eq!(frame.column("close")?.f64()?.get(0), Some(100.5));
+
+    Ok(())
+}
+
+#[test]
+fn csv_options_schema_overrides_inferred_dtype() -> anyhow::Result<()> {
+    let csv = write_csv("symbol,close\n001,100\n002,101\n")?;
+    let inferred = MarketData::from_csv(csv.path())?.collect()?;
+    assert_eq!(inferred.column("symbol")?.dtype(), &DataType::Int64);
+
+    let options = CsvOptions {
+        schema: Some(Schema::from_iter([Field::new("symbol", DataType::Utf8)])),
+        ..CsvOptions::default()
+    };
+    let frame = MarketData::from_csv_with_options(csv.path(), &options)?.collect()?;
+    assert_eq!(frame.column("symbol")?.dtype(), &DataType::Utf8);
+    assert_eq!(frame.column("symbol")?.utf8()?.get(0), Some("001"));
+    assert_eq!(frame.column("close")?.dtype(), &DataType::Int64);
+
+    Ok(())
+}
+
+const UNSORTED_CSV: &str = "timestamp,close\n2024-01-03T00:00:00Z,102\n2024-01-01T00:00:00Z,100\n2024-01-02T00:00:00Z,101\n";
+
+#[test]
+fn sort_by_time_orders_rows() -> anyhow::Result<()> {
+    let csv = write_csv(UNSORTED_CSV)?;
+    let market = MarketData::from_csv(csv.path())?;
+
+    let ascending = market.sort_by_time("timestamp", false)?;
+    let closes: Vec<i64> = ascending
+        .collect()?
+        .column("close")?
+        .i64()?
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(closes, vec![100, 101, 102]);
+    ascending.assert_sorted("timestamp")?;
+
+    let descending = market.sort_by_time("timestamp", true)?.collect()?;
+    let closes: Vec<i64> = descending
+        .column("close")?
+        .i64()?
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(closes, vec![102, 101, 100]);
+
+    Ok(())
+}
+
+#[test]
+fn assert_sorted_rejects_out_of_order_input() -> anyhow::Result<()> {
+    let csv = write_csv(UNSORTED_CSV)?;
+    let market = MarketData::from_csv(csv.path())?;
+
+    let error = market
+        .assert_sorted("timestamp")
+        .expect_err("unsorted input must be rejected");
+    assert!(matches!(error, DatasetError::Transform { .. }));
+
+    Ok(())
+}
+
+#[test]
+fn dedup_by_time_keeps_one_row_per_timestamp() -> anyhow::Result<()> {
+    let csv = write_csv(
+        "timestamp,close\n2024-01-01T00:00:00Z,100\n2024-01-02T00:00:00Z,101\n2024-01-02T00:00:00Z,105\n2024-01-03T00:00:00Z,102\n",
+    )?;
+    let market = MarketData::from_csv(csv.path())?;
+
+    for (keep, expected) in [
+        (KeepStrategy::First, vec![100, 101, 102]),
+        (KeepStrategy::Last, vec![100, 105, 102]),
+    ] {
+        let frame = market.dedup_by_time("timestamp", keep)?.collect()?;
+        let closes: Vec<i64> = frame.column("close")?.i64()?.into_no_null_iter().collect();
+        assert_eq!(closes, expected, "closes for {keep:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn from_csv_glob_concatenates_matching_files() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    std::fs::write(
+        dir.path().join("2024-01-01.csv"),
+        "timestamp,symbol,close\n2024-01-01T00:00:00Z,AAA,10\n2024-01-01T00:00:00Z,BBB,20\n",
+    )?;
+    std::fs::write(
+        dir.path().join("2024-01-02.csv"),
+        "timestamp,symbol,close\n2024-01-02T00:00:00Z,AAA,11\n2024-01-02T00:00:00Z,BBB,19\n2024-01-02T00:00:00Z,CCC,5\n",
+    )?;
+    std::fs::write(dir.path().join("notes.txt"), "not market data")?;
+
+    let pattern = dir.path().join("*.csv");
+    let market = MarketData::from_csv_glob(&pattern.to_string_lossy())?;
+    assert_eq!(market.collect()?.height(), 5);
+
+    let only_aaa = market.filter_symbols("symbol", &["AAA"])?.collect()?;
+    let closes: Vec<i64> = only_aaa
+        .column("close")?
+        .i64()?
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(closes, vec![10, 11]);
+
+    let missing = dir.path().join("*.parquet");
+    assert!(MarketData::from_csv_glob(&missing.to_string_lossy()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn collect_streaming_matches_in_memory_collect() -> anyhow::Result<()> {
+    let mut contents = String::from("timestamp,symbol,close\n");
+    for day in 0..500 {
+        let symbol = if day % 3 == 0 { "AAA" } else { "BBB" };
+        contents.push_str(&format!(
+            "2024-01-01T00:00:00Z,{symbol},{}\n",
+            100.0 + f64::from(day) * 0.5
+        ));
+    }
+    let csv = write_csv(&contents)?;
+
+    let pipeline = MarketData::from_csv(csv.path())?
+        .filter_symbols("symbol", &["AAA"])?
+        .select_columns(&["symbol", "close"])?;
+
+    let streamed = pipeline.collect_streaming()?;
+    let expected = pipeline.collect()?;
+    assert_eq!(streamed.height(), 167);
+    assert!(streamed.frame_equal(&expected));
+
+    Ok(())
+}
+
+#[test]
+fn from_csv_gz_matches_plain_csv() -> anyhow::Result<()> {
+    let plain = write_csv(PRICES_CSV)?;
+    let dir = tempdir()?;
+    let path = dir.path().join("prices.csv.gz");
+    let mut encoder = GzEncoder::new(File::create(&path)?, Compression::default());
+    encoder.write_all(PRICES_CSV.as_bytes())?;
+    encoder.finish()?;
+
+    let compressed = MarketData::from_csv_gz(&path)?.collect()?;
+    let expected = MarketData::from_csv(plain.path())?.collect()?;
+    assert_eq!(compressed.schema(), expected.schema());
+    assert!(compressed.frame_equal(&expected));
+
+    assert!(matches!(
+        MarketData::from_csv_gz(plain.path()),
+        Err(DatasetError::Load { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn from_csv_gz_with_options_honours_delimiter_and_schema() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("prices.csv.gz");
+    let mut encoder = GzEncoder::new(File::create(&path)?, Compression::default());
+    encoder.write_all(b"symbol;close\n001;100.5\n002;101\n")?;
+    encoder.finish()?;
+
+    let options = CsvOptions {
+        delimiter: b';',
+        schema: Some(Schema::from_iter([Field::new("symbol", DataType::Utf8)])),
+        ..CsvOptions::default()
+    };
+    let frame = MarketData::from_csv_gz_with_options(&path, &options)?.collect()?;
+    assert_eq!(frame.shape(), (2, 2));
+    assert_eq!(frame.column("symbol")?.utf8()?.get(0), Some("001"));
+    assert_eq!(frame.column("close")?.f64()?.get(0), Some(100.5));
+
+    Ok(())
+}
+
+#[test]
+fn pivot_wide_turns_symbols_into_columns() -> anyhow::Result<()> {
+    let csv = write_csv(
+        "timestamp,symbol,close\n2024-01-01T00:00:00Z,BBB,20\n2024-01-01T00:00:00Z,AAA,10\n2024-01-02T00:00:00Z,AAA,11\n",
+    )?;
+    let wide = MarketData::from_csv(csv.path())?.pivot_wide("timestamp", "symbol", "close")?;
+
+    assert_eq!(wide.shape(), (2, 3));
+    assert_eq!(wide.get_column_names(), ["timestamp", "AAA", "BBB"]);
+    let aaa: Vec<Option<i64>> = wide.column("AAA")?.i64()?.into_iter().collect();
+    let bbb: Vec<Option<i64>> = wide.column("BBB")?.i64()?.into_iter().collect();
+    assert_eq!(aaa, vec![Some(10), Some(11)]);
+    assert_eq!(bbb, vec![Some(20), None]);
+
+    Ok(())
+}
+
+#[test]
+fn filter_expr_keeps_matching_rows() -> anyhow::Result<()> {
+    let csv = write_csv(PRICES_CSV)?;
+    let filtered = MarketData::from_csv(csv.path())?
+        .filter_expr(col("close").gt(lit(100.0)))?
+        .collect()?;
+
+    let closes: Vec<i64> = filtered
+        .column("close")?
+        .i64()?
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(closes, vec![101, 102, 104, 103]);
+
+    Ok(())
+}
+
+#[test]
+fn head_tail_and_sample_preview_rows() -> anyhow::Result<()> {
+    let csv = write_csv(PRICES_CSV)?;
+    let market = MarketData::from_csv(csv.path())?;
+
+    let head = market.head(2)?;
+    let closes: Vec<i64> = head.column("close")?.i64()?.into_no_null_iter().collect();
+    assert_eq!(closes, vec![100, 101]);
+
+    let tail = market.tail(2)?;
+    let closes: Vec<i64> = tail.column("close")?.i64()?.into_no_null_iter().collect();
+    assert_eq!(closes, vec![104, 103]);
+
+    let sample = market.sample(3, Some(7))?;
+    assert_eq!(sample.height(), 3);
+    assert!(sample.frame_equal(&market.sample(3, Some(7))?));
+    assert!(matches!(
+        market.sample(10, Some(7)),
+        Err(DatasetError::Transform { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn schema_reports_columns_without_collecting() -> anyhow::Result<()> {
+    let csv = write_csv(PRICES_CSV)?;
+    let market = MarketData::from_csv(csv.path())?;
+
+    assert_eq!(market.column_names()?, ["timestamp", "close", "volume"]);
+    let schema = market.schema()?;
+    assert_eq!(schema.get("close"), Some(&DataType::Int64));
+    assert!(matches!(
+        schema.get("timestamp"),
+        Some(DataType::Datetime(..))
+    ));
+
+    let selected = market.select_columns(&["missing"])?;
+    assert!(matches!(
+        selected.column_names(),
+        Err(DatasetError::Transform { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn csv_options_can_disable_date_parsing() -> anyhow::Result<()> {
+    let csv = write_csv("period,listed,close\n2024Q1,2024-01-01,100\n2024Q2,2024-04-01,101\n")?;
+    let options = CsvOptions {
+        parse_dates: false,
+        ..CsvOptions::default()
+    };
+
+    let raw = MarketData::from_csv_with_options(csv.path(), &options)?.collect()?;
+    assert_eq!(raw.column("period")?.dtype(), &DataType::Utf8);
+    assert_eq!(raw.column("listed")?.dtype(), &DataType::Utf8);
+    assert_eq!(raw.column("listed")?.utf8()?.get(0), Some("2024-01-01"));
+    assert_eq!(raw.column("close")?.dtype(), &DataType::Int64);
+
+    let parsed = MarketData::from_csv(csv.path())?.collect()?;
+    assert_eq!(parsed.column("period")?.dtype(), &DataType::Utf8);
+    assert_eq!(parsed.column("listed")?.dtype(), &DataType::Date);
+
+    Ok(())
+}
+
+#[test]
+fn filter_date_range_respects_timezone_aware_columns() -> anyhow::Result<()> {
+    let day_ms = 86_400_000_i64;
+    let first = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let millis: Vec<i64> = (0..5)
+        .map(|day| first.timestamp_millis() + day * day_ms)
+        .collect();
+    let timestamps = Series::new("timestamp", millis).cast(&DataType::Datetime(
+        TimeUnit::Milliseconds,
+        Some("America/New_York".into()),
+    ))?;
+    let frame = DataFrame::new(vec![
+        timestamps,
+        Series::new("close", [1.0, 2.0, 3.0, 4.0, 5.0]),
+    ])?;
+    let market = MarketData::from_dataframe(frame);
+
+    let start = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 1, 4, 0, 0, 0).unwrap();
+    let filtered = market
+        .filter_date_range("timestamp", Some(start), Some(end))?
+        .collect()?;
+    assert_eq!(
+        filtered
+            .column("close")?
+            .f64()?
+            .into_no_null_iter()
+            .collect::<Vec<_>>(),
+        vec![2.0, 3.0, 4.0]
+    );
+
+    let just_after = start + chrono::Duration::milliseconds(1);
+    let filtered = market
+        .filter_date_range("timestamp", Some(just_after), None)?
+        .collect()?;
+    assert_eq!(filtered.height(), 3);
+
+    assert!(matches!(
+        market.filter_date_range("close", Some(start), None),
+        Err(DatasetError::NotTemporal { ref column, .. }) if column == "close"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn exclusive_end_bound_drops_the_row_at_end() -> anyhow::Result<()> {
+    let csv = write_csv(PRICES_CSV)?;
+    let market = MarketData::from_csv(csv.path())?;
+    let start = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 1, 4, 0, 0, 0).unwrap();
+
+    let closes = |bounds: IntervalBounds| -> anyhow::Result<Vec<i64>> {
+        let frame = market
+            .filter_date_range_with_bounds("timestamp", Some(start), Some(end), bounds)?
+            .collect()?;
+        Ok(frame.column("close")?.i64()?.into_no_null_iter().collect())
+    };
+
+    assert_eq!(closes(IntervalBounds::default())?, vec![101, 102, 104]);
+    assert_eq!(closes(IntervalBounds::ClosedOpen)?, vec![101, 102]);
+    assert_eq!(closes(IntervalBounds::OpenClosed)?, vec![102, 104]);
+    assert_eq!(closes(IntervalBounds::Open)?, vec![102]);
+    assert_eq!(
+        market
+            .filter_date_range("timestamp", Some(start), Some(end))?
+            .collect()?,
+        market
+            .filter_date_range_with_bounds(
+                "timestamp",
+                Some(start),
+                Some(end),
+                IntervalBounds::Closed
+            )?
+            .collect()?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn validate_schema_names_the_first_offending_column() -> anyhow::Result<()> {
+    let csv = write_csv("timestamp,symbol,close,volume\n2024-01-01T00:00:00Z,AAA,10.5,100\n")?;
+    let market = MarketData::from_csv(csv.path())?;
+    let datetime = DataType::Datetime(TimeUnit::Microseconds, None);
+
+    market.validate_schema(&[
+        ("timestamp", datetime.clone()),
+        ("close", DataType::Float64),
+        ("volume", DataType::Float64),
+        ("symbol", DataType::Utf8),
+    ])?;
+
+    let error = market
+        .validate_schema(&[
+            ("close", DataType::Float64),
+            ("symbol", DataType::Float64),
+            ("open", DataType::Float64),
+        ])
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        DatasetError::IncompatibleDtype { ref column, .. } if column == "symbol"
+    ));
+    assert!(error.to_string().contains("symbol"));
+
+    assert!(matches!(
+        market.validate_schema(&[("open", DataType::Float64), ("symbol", DataType::Float64)]),
+        Err(DatasetError::MissingColumn(ref column)) if column == "open"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn rename_normalizes_vendor_column_names() -> anyhow::Result<()> {
+    let csv = write_csv("Date,Close\n2024-01-01,100\n2024-01-02,102\n2024-01-03,101\n")?;
+    let market = MarketData::from_csv(csv.path())?;
+
+    let renamed = market.rename(&[("Close", "close"), ("Date", "timestamp")])?;
+    assert_eq!(renamed.column_names()?, vec!["timestamp", "close"]);
+    let enriched = with_daily_returns(&renamed.collect()?, "close", "return")?;
+    let returns: Vec<f64> = enriched
+        .column("return")?
+        .f64()?
+        .into_no_null_iter()
+        .collect();
+    assert!((returns[1] - 0.02).abs() < 1e-12);
+
+    assert!(matches!(
+        market.rename(&[("Open", "open")]),
+        Err(DatasetError::Transform { .. })
+    ));
+
+    Ok(())
+}
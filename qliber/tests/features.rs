@@ -0,0 +1,916 @@
+use approx::assert_abs_diff_eq;
+use polars::prelude::*;
+
+use qliber::features::{
+    FeatureError, NullPolicy, daily_returns_expr, moving_average_expr, with_bollinger_bands,
+    with_cross_sectional_rank, with_cross_sectional_z_score, with_cumulative_return,
+    with_daily_returns, with_daily_returns_grouped, with_daily_returns_with_policy, with_ema,
+    with_expanding_mean, with_expanding_percentile_rank, with_expanding_std, with_forward_fill,
+    with_lag, with_lag_filled, with_log_returns, with_macd, with_min_max_scaled,
+    with_moving_average, with_moving_average_multi, with_moving_average_strict,
+    with_moving_average_with_policy, with_pct_change, with_reconstructed_price, with_residual,
+    with_robust_z_score, with_rolling_apply, with_rolling_beta, with_rolling_max, with_rolling_min,
+    with_rolling_min_max_scaled, with_rolling_quantile, with_rolling_std, with_rsi, with_vwap,
+    with_winsorized, with_z_score, with_z_score_with_policy, z_score_expr,
+};
+use qliber::metrics::{AccumulationMode, PerformanceMetrics};
+
+fn column_values(frame: &DataFrame, column: &str) -> Vec<f64> {
+    frame
+        .column(column)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+#[test]
+fn ema_matches_hand_computed_sequence() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[10.0, 13.0, 16.0, 13.0] }?;
+    let enriched = with_ema(&frame, "close", 3, "ema_3")?;
+    let ema = column_values(&enriched, "ema_3");
+
+    // alpha = 2 / (3 + 1) = 0.5
+    let expected = [10.0, 11.5, 13.75, 13.375];
+    for (actual, expected) in ema.iter().zip(expected) {
+        assert_abs_diff_eq!(*actual, expected, epsilon = 1e-12);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn ema_converges_toward_constant_input() -> anyhow::Result<()> {
+    let mut prices = vec![0.0];
+    prices.extend(std::iter::repeat_n(50.0, 60));
+    let frame = df! { "close" => prices }?;
+
+    let enriched = with_ema(&frame, "close", 5, "ema_5")?;
+    let ema = column_values(&enriched, "ema_5");
+
+    assert_eq!(ema[0], 0.0);
+    assert!(ema.windows(2).skip(1).all(|pair| pair[1] >= pair[0]));
+    assert_abs_diff_eq!(*ema.last().unwrap(), 50.0, epsilon = 1e-6);
+
+    Ok(())
+}
+
+#[test]
+fn rsi_saturates_on_rising_prices() -> anyhow::Result<()> {
+    let prices: Vec<f64> = (0..30).map(|i| 100.0 + i as f64).collect();
+    let frame = df! { "close" => prices }?;
+
+    let enriched = with_rsi(&frame, "close", 14, "rsi_14")?;
+    let rsi = column_values(&enriched, "rsi_14");
+
+    assert!(rsi[..14].iter().all(|value| *value == 50.0));
+    assert!(rsi[14..].iter().all(|value| *value > 99.9));
+
+    Ok(())
+}
+
+#[test]
+fn rsi_applies_wilder_smoothing() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[10.0, 11.0, 10.0, 12.0, 11.0] }?;
+    let enriched = with_rsi(&frame, "close", 2, "rsi_2")?;
+    let rsi = column_values(&enriched, "rsi_2");
+
+    // Seed averages: gain 0.5, loss 0.5 -> 50; then gain 1.25, loss 0.25; then 0.625, 0.625.
+    assert_abs_diff_eq!(rsi[2], 50.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(rsi[3], 100.0 - 100.0 / 6.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(rsi[4], 50.0, epsilon = 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn macd_histogram_is_macd_minus_signal() -> anyhow::Result<()> {
+    let prices: Vec<f64> = (0..40)
+        .map(|i| 100.0 + (i as f64 * 0.3).sin() * 5.0 + i as f64 * 0.1)
+        .collect();
+    let frame = df! { "close" => prices }?;
+
+    let enriched = with_macd(&frame, "close", 12, 26, 9, "trend")?;
+    let macd = column_values(&enriched, "trend_macd");
+    let signal = column_values(&enriched, "trend_signal");
+    let hist = column_values(&enriched, "trend_hist");
+
+    assert_eq!(hist.len(), 40);
+    assert_eq!(macd[0], 0.0);
+    for ((macd, signal), hist) in macd.iter().zip(&signal).zip(&hist) {
+        assert_abs_diff_eq!(*hist, macd - signal, epsilon = 1e-12);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn macd_rejects_invalid_spans() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[1.0, 2.0, 3.0] }?;
+
+    assert!(with_macd(&frame, "close", 26, 12, 9, "trend").is_err());
+    assert!(with_macd(&frame, "close", 0, 12, 9, "trend").is_err());
+    assert!(with_macd(&frame, "close", 12, 26, 0, "trend").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn bollinger_bands_collapse_on_constant_series() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[42.0; 8] }?;
+    let enriched = with_bollinger_bands(&frame, "close", 4, 2.0, "bb")?;
+
+    for column in ["bb_mid", "bb_upper", "bb_lower"] {
+        for value in column_values(&enriched, column) {
+            assert_abs_diff_eq!(value, 42.0, epsilon = 1e-9);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn bollinger_bands_use_partial_windows_then_trailing_window() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[1.0, 3.0, 5.0, 7.0] }?;
+    let enriched = with_bollinger_bands(&frame, "close", 2, 1.0, "bb")?;
+
+    let mid = column_values(&enriched, "bb_mid");
+    let upper = column_values(&enriched, "bb_upper");
+    let lower = column_values(&enriched, "bb_lower");
+
+    assert_abs_diff_eq!(mid[0], 1.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(upper[0], 1.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(mid[3], 6.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(upper[3], 7.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(lower[3], 5.0, epsilon = 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn log_returns_sum_to_total_log_growth() -> anyhow::Result<()> {
+    let prices = [100.0_f64, 101.5, 99.0, 104.0, 103.2];
+    let frame = df! { "close" => &prices }?;
+
+    let enriched = with_log_returns(&frame, "close", "log_return")?;
+    let returns = column_values(&enriched, "log_return");
+
+    assert_eq!(returns[0], 0.0);
+    assert_abs_diff_eq!(
+        returns.iter().sum::<f64>(),
+        (prices[4] / prices[0]).ln(),
+        epsilon = 1e-12
+    );
+
+    Ok(())
+}
+
+#[test]
+fn log_returns_zero_out_non_positive_prices() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[100.0, 0.0, 50.0, 100.0] }?;
+    let enriched = with_log_returns(&frame, "close", "log_return")?;
+    let returns = column_values(&enriched, "log_return");
+
+    assert!(returns.iter().all(|value| value.is_finite()));
+    assert_eq!(returns[1], 0.0);
+    assert_eq!(returns[2], 0.0);
+    assert_abs_diff_eq!(returns[3], 2.0_f64.ln(), epsilon = 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn rolling_std_matches_brute_force_sample_std() -> anyhow::Result<()> {
+    let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+    let frame = df! { "x" => &values }?;
+    let window = 4;
+
+    let enriched = with_rolling_std(&frame, "x", window, "x_std")?;
+    let rolling = column_values(&enriched, "x_std");
+
+    assert_eq!(rolling[0], 0.0);
+    for (idx, actual) in rolling.iter().enumerate().skip(1) {
+        let slice = &values[idx.saturating_sub(window - 1)..=idx];
+        let mean = slice.iter().sum::<f64>() / slice.len() as f64;
+        let variance =
+            slice.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (slice.len() as f64 - 1.0);
+        assert_abs_diff_eq!(*actual, variance.sqrt(), epsilon = 1e-9);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn rolling_max_tracks_local_peak_for_window_periods() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[1.0, 2.0, 9.0, 3.0, 2.0, 1.0, 4.0] }?;
+    let window = 3;
+
+    let enriched = with_rolling_max(&frame, "close", window, "close_max")?;
+    let enriched = with_rolling_min(&enriched, "close", window, "close_min")?;
+    let maximums = column_values(&enriched, "close_max");
+    let minimums = column_values(&enriched, "close_min");
+
+    assert_eq!(maximums, vec![1.0, 2.0, 9.0, 9.0, 9.0, 3.0, 4.0]);
+    assert_eq!(minimums, vec![1.0, 1.0, 1.0, 2.0, 2.0, 1.0, 1.0]);
+
+    Ok(())
+}
+
+#[test]
+fn cumulative_return_matches_product_mode_metrics() -> anyhow::Result<()> {
+    let returns = [0.01, -0.015, 0.02, -0.005];
+    let frame = df! { "return" => &returns }?;
+
+    let enriched = with_cumulative_return(&frame, "return", "cum_return")?;
+    let cumulative = column_values(&enriched, "cum_return");
+    let metrics =
+        PerformanceMetrics::evaluate_with_mode(&returns, 252.0, AccumulationMode::Product);
+
+    assert_abs_diff_eq!(cumulative[0], 0.01, epsilon = 1e-12);
+    assert_abs_diff_eq!(
+        *cumulative.last().unwrap(),
+        metrics.cumulative_return,
+        epsilon = 1e-12
+    );
+
+    Ok(())
+}
+
+#[test]
+fn cumulative_return_skips_non_finite_returns() -> anyhow::Result<()> {
+    let frame = df! { "return" => &[0.1, f64::NAN, 0.1] }?;
+    let enriched = with_cumulative_return(&frame, "return", "cum_return")?;
+    let cumulative = column_values(&enriched, "cum_return");
+
+    assert_abs_diff_eq!(cumulative[1], 0.1, epsilon = 1e-12);
+    assert_abs_diff_eq!(cumulative[2], 0.21, epsilon = 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn cross_sectional_rank_normalizes_within_each_timestamp() -> anyhow::Result<()> {
+    let frame = df! {
+        "date" => &["2024-01-01", "2024-01-01", "2024-01-01", "2024-01-02", "2024-01-02", "2024-01-02"],
+        "symbol" => &["A", "B", "C", "A", "B", "C"],
+        "factor" => &[0.3, 0.9, 0.1, 5.0, -1.0, 5.0],
+    }?;
+
+    let enriched = with_cross_sectional_rank(&frame, "factor", "date", "factor_rank")?;
+    let ranks = column_values(&enriched, "factor_rank");
+
+    assert_abs_diff_eq!(ranks[0], 2.0 / 3.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(ranks[1], 1.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(ranks[2], 1.0 / 3.0, epsilon = 1e-12);
+
+    // Ties share the average of ranks 2 and 3.
+    assert_abs_diff_eq!(ranks[3], 2.5 / 3.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(ranks[4], 1.0 / 3.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(ranks[5], 2.5 / 3.0, epsilon = 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn winsorized_pulls_outlier_to_upper_quantile() -> anyhow::Result<()> {
+    let frame = df! { "x" => &[1.0, 2.0, 3.0, 4.0, 100.0, f64::NAN] }?;
+    let enriched = with_winsorized(&frame, "x", 0.0, 0.75, "x_clipped")?;
+
+    let clipped: Vec<f64> = enriched
+        .column("x_clipped")?
+        .f64()?
+        .into_no_null_iter()
+        .collect();
+
+    assert_eq!(&clipped[..5], &[1.0, 2.0, 3.0, 4.0, 4.0]);
+    assert!(clipped[5].is_nan());
+
+    Ok(())
+}
+
+#[test]
+fn winsorized_keeps_nulls_and_always_writes_output() -> anyhow::Result<()> {
+    let frame = df! { "x" => &[Some(1.0), None, Some(2.0), Some(3.0), Some(100.0)] }?;
+    let enriched = with_winsorized(&frame, "x", 0.0, 0.5, "x_clipped")?;
+    assert_eq!(
+        optional_values(&enriched, "x_clipped"),
+        vec![Some(1.0), None, Some(2.0), Some(2.5), Some(2.5)]
+    );
+
+    let no_finite = df! { "x" => &[None, Some(f64::NAN), None] }?;
+    let enriched = with_winsorized(&no_finite, "x", 0.1, 0.9, "x_clipped")?;
+    let passed = optional_values(&enriched, "x_clipped");
+    assert_eq!(passed.len(), 3);
+    assert_eq!(passed[0], None);
+    assert!(passed[1].is_some_and(f64::is_nan));
+    assert_eq!(passed[2], None);
+
+    Ok(())
+}
+
+#[test]
+fn winsorized_rejects_invalid_quantiles() -> anyhow::Result<()> {
+    let frame = df! { "x" => &[1.0, 2.0, 3.0] }?;
+
+    assert!(with_winsorized(&frame, "x", 0.9, 0.1, "out").is_err());
+    assert!(with_winsorized(&frame, "x", -0.1, 0.5, "out").is_err());
+    assert!(with_winsorized(&frame, "x", 0.1, 1.5, "out").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn lag_reproduces_previous_row_and_lead_shifts_upward() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[1.0, 2.0, 3.0, 4.0] }?;
+
+    let lagged = with_lag(&frame, "close", 1, "close_lag1")?;
+    let lag: Vec<Option<f64>> = lagged.column("close_lag1")?.f64()?.into_iter().collect();
+    assert_eq!(lag, vec![None, Some(1.0), Some(2.0), Some(3.0)]);
+
+    let led = with_lag(&frame, "close", -1, "close_lead1")?;
+    let lead: Vec<Option<f64>> = led.column("close_lead1")?.f64()?.into_iter().collect();
+    assert_eq!(lead, vec![Some(2.0), Some(3.0), Some(4.0), None]);
+
+    let filled = with_lag_filled(&frame, "close", 2, 0.0, "close_lag2")?;
+    assert_eq!(
+        column_values(&filled, "close_lag2"),
+        vec![0.0, 0.0, 1.0, 2.0]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lag_equal_to_frame_length_is_all_null() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[1.0, 2.0, 3.0] }?;
+    let lagged = with_lag(&frame, "close", 3, "close_lag3")?;
+
+    assert_eq!(lagged.column("close_lag3")?.null_count(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn expanding_statistics_use_full_history() -> anyhow::Result<()> {
+    let prices = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+    let frame = df!("close" => &prices)?;
+
+    let enriched = with_expanding_mean(&frame, "close", "mean")?;
+    let enriched = with_expanding_std(&enriched, "close", "std")?;
+    let means = column_values(&enriched, "mean");
+    let stds = column_values(&enriched, "std");
+
+    let full_mean = prices.iter().sum::<f64>() / prices.len() as f64;
+    assert_abs_diff_eq!(means[prices.len() - 1], full_mean, epsilon = 1e-12);
+    assert_abs_diff_eq!(means[1], 2.0, epsilon = 1e-12);
+
+    let full_variance = prices
+        .iter()
+        .map(|price| (price - full_mean).powi(2))
+        .sum::<f64>()
+        / (prices.len() as f64 - 1.0);
+    assert_eq!(stds[0], 0.0);
+    assert_abs_diff_eq!(stds[1], 2.0_f64.sqrt(), epsilon = 1e-12);
+    assert_abs_diff_eq!(
+        stds[prices.len() - 1],
+        full_variance.sqrt(),
+        epsilon = 1e-12
+    );
+
+    let empty = df!("close" => Vec::<f64>::new())?;
+    assert_eq!(with_expanding_mean(&empty, "close", "mean")?.width(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn robust_z_score_resists_single_spike() -> anyhow::Result<()> {
+    let mut prices: Vec<f64> = (0..20).map(|idx| 100.0 + (idx % 4) as f64).collect();
+    prices[10] = 500.0;
+    let frame = df!("close" => &prices)?;
+
+    let enriched = with_z_score(&frame, "close", 8, "z")?;
+    let enriched = with_robust_z_score(&enriched, "close", 8, "robust_z")?;
+    let standard = column_values(&enriched, "z");
+    let robust = column_values(&enriched, "robust_z");
+
+    assert!(robust[10] > standard[10]);
+    assert!(robust[10] > 10.0);
+    for (idx, value) in robust.iter().enumerate().skip(11).take(7) {
+        assert!(value.abs() < 2.0, "row {idx}: {value}");
+    }
+
+    let flat = df!("close" => &[5.0, 5.0, 5.0])?;
+    let flat = with_robust_z_score(&flat, "close", 3, "robust_z")?;
+    assert_eq!(column_values(&flat, "robust_z"), vec![0.0, 0.0, 0.0]);
+
+    Ok(())
+}
+
+#[test]
+fn vwap_of_constant_price_is_that_price() -> anyhow::Result<()> {
+    let frame = df!(
+        "close" => &[50.0, 50.0, 50.0, 50.0, 50.0],
+        "volume" => &[10.0, 0.0, 250.0, 3.0, 1.0]
+    )?;
+    let enriched = with_vwap(&frame, "close", "volume", 3, "vwap")?;
+    for value in column_values(&enriched, "vwap") {
+        assert_abs_diff_eq!(value, 50.0, epsilon = 1e-9);
+    }
+
+    let weighted = df!(
+        "close" => &[10.0, 20.0, 30.0],
+        "volume" => &[0.0, 1.0, 3.0]
+    )?;
+    let enriched = with_vwap(&weighted, "close", "volume", 2, "vwap")?;
+    let vwap: Vec<Option<f64>> = enriched.column("vwap")?.f64()?.into_iter().collect();
+    assert_eq!(vwap, vec![None, Some(20.0), Some(27.5)]);
+
+    Ok(())
+}
+
+#[test]
+fn min_max_scaling_maps_ramp_into_unit_interval() -> anyhow::Result<()> {
+    let frame = df!("close" => &[10.0, 20.0, 30.0, 40.0, 50.0])?;
+
+    let global = with_min_max_scaled(&frame, "close", "scaled")?;
+    assert_eq!(
+        column_values(&global, "scaled"),
+        vec![0.0, 0.25, 0.5, 0.75, 1.0]
+    );
+
+    let rolling = with_rolling_min_max_scaled(&frame, "close", 3, "scaled")?;
+    assert_eq!(
+        column_values(&rolling, "scaled"),
+        vec![0.5, 1.0, 1.0, 1.0, 1.0]
+    );
+
+    let constant = df!("close" => &[7.0, 7.0, 7.0])?;
+    let constant = with_min_max_scaled(&constant, "close", "scaled")?;
+    assert_eq!(column_values(&constant, "scaled"), vec![0.5, 0.5, 0.5]);
+
+    Ok(())
+}
+
+#[test]
+fn pct_change_spans_multiple_periods() -> anyhow::Result<()> {
+    let frame = df!("close" => &[100.0, 110.0, 121.0])?;
+    let changes = column_values(&with_pct_change(&frame, "close", 2, "pct")?, "pct");
+    assert_eq!(changes[..2], [0.0, 0.0]);
+    assert_abs_diff_eq!(changes[2], 0.21, epsilon = 1e-12);
+
+    let zero_base = df!("close" => &[0.0, 5.0, 10.0])?;
+    let changes = column_values(&with_pct_change(&zero_base, "close", 1, "pct")?, "pct");
+    assert_eq!(changes, vec![0.0, 0.0, 1.0]);
+
+    let short = df!("close" => &[100.0])?;
+    let changes = column_values(&with_pct_change(&short, "close", 3, "pct")?, "pct");
+    assert_eq!(changes, vec![0.0]);
+
+    Ok(())
+}
+
+#[test]
+fn rolling_quantile_median_matches_window_median() -> anyhow::Result<()> {
+    let frame = df!("close" => &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0])?;
+
+    let medians = column_values(&with_rolling_quantile(&frame, "close", 3, 0.5, "q")?, "q");
+    assert_eq!(medians, vec![1.0, 1.5, 2.0, 3.0, 4.0, 5.0]);
+
+    let upper = column_values(&with_rolling_quantile(&frame, "close", 3, 1.0, "q")?, "q");
+    assert_eq!(upper, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+    assert!(with_rolling_quantile(&frame, "close", 3, 1.5, "q").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn moving_average_multi_covers_every_column() -> anyhow::Result<()> {
+    let frame = df!(
+        "open" => &[1.0, 3.0, 5.0],
+        "close" => &[2.0, 4.0, 8.0]
+    )?;
+
+    let enriched = with_moving_average_multi(&frame, &["open", "close"], 2, "_ma2")?;
+    assert_eq!(column_values(&enriched, "open_ma2"), vec![1.0, 2.0, 4.0]);
+    assert_eq!(column_values(&enriched, "close_ma2"), vec![2.0, 3.0, 6.0]);
+
+    assert!(with_moving_average_multi(&frame, &["open", "missing"], 2, "_ma2").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn grouped_daily_returns_stay_within_symbol() -> anyhow::Result<()> {
+    let frame = df!(
+        "symbol" => &["AAA", "BBB", "AAA", "BBB", "AAA"],
+        "close" => &[100.0, 50.0, 110.0, 45.0, 99.0]
+    )?;
+
+    let enriched = with_daily_returns_grouped(&frame, "symbol", "close", "return")?;
+    let symbols: Vec<&str> = enriched
+        .column("symbol")?
+        .utf8()?
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(symbols, vec!["AAA", "BBB", "AAA", "BBB", "AAA"]);
+
+    let returns = column_values(&enriched, "return");
+    assert_eq!(returns[0], 0.0);
+    assert_eq!(returns[1], 0.0);
+    assert_abs_diff_eq!(returns[2], 0.1, epsilon = 1e-12);
+    assert_abs_diff_eq!(returns[3], -0.1, epsilon = 1e-12);
+    assert_abs_diff_eq!(returns[4], -0.1, epsilon = 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn forward_fill_carries_prior_value_over_gaps() -> anyhow::Result<()> {
+    let frame = df!("close" => &[None, Some(100.0), None, None, Some(103.0)])?;
+
+    let filled = with_forward_fill(&frame, "close", None, "close_ff")?;
+    let values: Vec<Option<f64>> = filled.column("close_ff")?.f64()?.into_iter().collect();
+    assert_eq!(
+        values,
+        vec![None, Some(100.0), Some(100.0), Some(100.0), Some(103.0)]
+    );
+
+    let limited = with_forward_fill(&frame, "close", Some(1), "close_ff")?;
+    let values: Vec<Option<f64>> = limited.column("close_ff")?.f64()?.into_iter().collect();
+    assert_eq!(
+        values,
+        vec![None, Some(100.0), Some(100.0), None, Some(103.0)]
+    );
+
+    let returns = with_daily_returns(&filled.slice(1, 4), "close_ff", "return")?;
+    assert_eq!(column_values(&returns, "return")[..3], [0.0, 0.0, 0.0]);
+
+    Ok(())
+}
+
+fn optional_values(frame: &DataFrame, column: &str) -> Vec<Option<f64>> {
+    frame
+        .column(column)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn null_policy_controls_daily_returns() -> anyhow::Result<()> {
+    let frame = df!("close" => &[Some(100.0), None, Some(110.0)])?;
+
+    let zero = with_daily_returns_with_policy(&frame, "close", "r", NullPolicy::Zero)?;
+    assert_eq!(
+        optional_values(&zero, "r"),
+        optional_values(&with_daily_returns(&frame, "close", "r")?, "r")
+    );
+    assert_eq!(
+        optional_values(&zero, "r"),
+        vec![Some(0.0), Some(-1.0), Some(0.0)]
+    );
+
+    let skip = with_daily_returns_with_policy(&frame, "close", "r", NullPolicy::Skip)?;
+    let skipped = optional_values(&skip, "r");
+    assert_eq!(skipped[..2], [Some(0.0), None]);
+    assert_abs_diff_eq!(skipped[2].unwrap(), 0.1, epsilon = 1e-12);
+
+    let filled = with_daily_returns_with_policy(&frame, "close", "r", NullPolicy::ForwardFill)?;
+    let filled = optional_values(&filled, "r");
+    assert_eq!(filled[..2], [Some(0.0), Some(0.0)]);
+    assert_abs_diff_eq!(filled[2].unwrap(), 0.1, epsilon = 1e-12);
+
+    let error = with_daily_returns_with_policy(&frame, "close", "r", NullPolicy::Error)
+        .expect_err("nulls must be rejected");
+    assert!(error.to_string().contains("close"));
+
+    Ok(())
+}
+
+#[test]
+fn null_policy_controls_rolling_features() -> anyhow::Result<()> {
+    let frame = df!("close" => &[None, Some(2.0), Some(4.0), None, Some(6.0)])?;
+
+    let zero = with_moving_average_with_policy(&frame, "close", 2, "ma", NullPolicy::Zero)?;
+    assert_eq!(
+        optional_values(&zero, "ma"),
+        vec![Some(0.0), Some(1.0), Some(3.0), Some(2.0), Some(3.0)]
+    );
+
+    let skip = with_moving_average_with_policy(&frame, "close", 2, "ma", NullPolicy::Skip)?;
+    assert_eq!(
+        optional_values(&skip, "ma"),
+        vec![None, Some(2.0), Some(3.0), None, Some(5.0)]
+    );
+
+    let filled =
+        with_moving_average_with_policy(&frame, "close", 2, "ma", NullPolicy::ForwardFill)?;
+    assert_eq!(
+        optional_values(&filled, "ma"),
+        vec![None, Some(2.0), Some(3.0), Some(4.0), Some(5.0)]
+    );
+
+    assert!(with_moving_average_with_policy(&frame, "close", 2, "ma", NullPolicy::Error).is_err());
+
+    let skip_z = with_z_score_with_policy(&frame, "close", 2, "z", NullPolicy::Skip)?;
+    let skip_z = optional_values(&skip_z, "z");
+    assert_eq!(skip_z[0], None);
+    assert_eq!(skip_z[3], None);
+    assert_eq!(skip_z[1], Some(0.0));
+    assert_abs_diff_eq!(skip_z[2].unwrap(), 1.0, epsilon = 1e-12);
+    assert!(with_z_score_with_policy(&frame, "close", 2, "z", NullPolicy::Error).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn strict_moving_average_rejects_oversized_window() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[1.0, 2.0, 3.0] }?;
+
+    let error = with_moving_average_strict(&frame, "close", 5, "ma")
+        .expect_err("window longer than the frame must error");
+    assert!(matches!(
+        error,
+        FeatureError::WindowExceedsRows { window: 5, rows: 3 }
+    ));
+
+    let lenient = with_moving_average(&frame, "close", 5, "ma")?;
+    assert_eq!(column_values(&lenient, "ma"), vec![1.0, 1.5, 2.0]);
+
+    let strict = with_moving_average_strict(&frame, "close", 3, "ma")?;
+    assert!(strict.frame_equal(&with_moving_average(&frame, "close", 3, "ma")?));
+
+    Ok(())
+}
+
+#[test]
+fn z_score_expr_matches_eager_z_score() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[10.0, 11.0, 10.5, 12.0, 12.0, 12.0, 11.0, 13.5] }?;
+
+    let eager = with_z_score(&frame, "close", 3, "z")?;
+    let lazy = frame
+        .clone()
+        .lazy()
+        .with_column(z_score_expr("close", 3)?.alias("z"))
+        .collect()?;
+
+    for (actual, expected) in column_values(&lazy, "z")
+        .into_iter()
+        .zip(column_values(&eager, "z"))
+    {
+        assert_abs_diff_eq!(actual, expected, epsilon = 1e-9);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lazy_return_and_average_exprs_match_eager_features() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[100.0, 102.0, 0.0, 5.0, 5.5, 5.0, 6.0] }?;
+
+    let eager = with_moving_average(
+        &with_daily_returns(&frame, "close", "ret")?,
+        "close",
+        3,
+        "ma",
+    )?;
+    let lazy = frame
+        .clone()
+        .lazy()
+        .with_columns([
+            daily_returns_expr("close").alias("ret"),
+            moving_average_expr("close", 3)?.alias("ma"),
+        ])
+        .collect()?;
+
+    for column in ["ret", "ma"] {
+        for (actual, expected) in column_values(&lazy, column)
+            .into_iter()
+            .zip(column_values(&eager, column))
+        {
+            assert_abs_diff_eq!(actual, expected, epsilon = 1e-12);
+        }
+    }
+    assert_eq!(column_values(&lazy, "ret")[0], 0.0);
+    assert_eq!(column_values(&lazy, "ret")[3], 0.0);
+
+    Ok(())
+}
+
+#[test]
+fn rolling_beta_recovers_leverage_of_asset_on_market() -> anyhow::Result<()> {
+    let market = [0.01, -0.02, 0.015, 0.003, -0.007, 0.012];
+    let asset: Vec<f64> = market.iter().map(|value| 2.0 * value).collect();
+    let frame = df! { "asset" => &asset, "market" => &market }?;
+
+    let enriched = with_rolling_beta(&frame, "asset", "market", 3, "beta")?;
+    let betas = column_values(&enriched, "beta");
+
+    assert_eq!(betas[0], 0.0);
+    for beta in &betas[2..] {
+        assert_abs_diff_eq!(*beta, 2.0, epsilon = 1e-9);
+    }
+
+    let flat = df! { "asset" => &[0.01, 0.02, 0.03], "market" => &[0.005, 0.005, 0.005] }?;
+    let flat_betas = column_values(
+        &with_rolling_beta(&flat, "asset", "market", 2, "beta")?,
+        "beta",
+    );
+    assert_eq!(flat_betas, vec![0.0, 0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn residual_vanishes_when_y_tracks_x() -> anyhow::Result<()> {
+    let x = [0.01, -0.02, 0.015, 0.003, -0.007, 0.012];
+    let frame = df! { "y" => &x, "x" => &x }?;
+
+    let enriched = with_residual(&frame, "y", "x", 3, "residual")?;
+    let residuals = column_values(&enriched, "residual");
+
+    assert_eq!(residuals[0], x[0]);
+    for residual in &residuals[1..] {
+        assert_abs_diff_eq!(*residual, 0.0, epsilon = 1e-12);
+    }
+
+    let shifted: Vec<f64> = x.iter().map(|value| 3.0 * value + 0.5).collect();
+    let frame = df! { "y" => &shifted, "x" => &x }?;
+    let residuals = column_values(&with_residual(&frame, "y", "x", 4, "residual")?, "residual");
+    for residual in &residuals[1..] {
+        assert_abs_diff_eq!(*residual, 0.0, epsilon = 1e-9);
+    }
+    Ok(())
+}
+
+#[test]
+fn cross_sectional_z_score_standardizes_each_timestamp() -> anyhow::Result<()> {
+    let frame = df! {
+        "date" => &["d1", "d1", "d1", "d2", "d2", "d2", "d3"],
+        "factor" => &[1.0, 2.0, 3.0, 10.0, 30.0, 20.0, 5.0],
+    }?;
+
+    let enriched = with_cross_sectional_z_score(&frame, "factor", "date", "factor_z")?;
+    let z = column_values(&enriched, "factor_z");
+
+    let expected = [-1.0, 0.0, 1.0, -1.0, 1.0, 0.0, 0.0];
+    for (actual, expected) in z.iter().zip(expected) {
+        assert_abs_diff_eq!(*actual, expected, epsilon = 1e-12);
+    }
+
+    let flat = df! { "date" => &["d1", "d1"], "factor" => &[4.0, 4.0] }?;
+    let flat_z = with_cross_sectional_z_score(&flat, "factor", "date", "factor_z")?;
+    assert_eq!(column_values(&flat_z, "factor_z"), vec![0.0, 0.0]);
+    Ok(())
+}
+
+#[test]
+fn expanding_percentile_rank_only_looks_backwards() -> anyhow::Result<()> {
+    let frame = df! {
+        "rising" => &[1.0, 2.0, 3.0, 4.0],
+        "falling" => &[4.0, 3.0, 2.0, 1.0],
+    }?;
+
+    let enriched = with_expanding_percentile_rank(&frame, "rising", "rising_rank")?;
+    let enriched = with_expanding_percentile_rank(&enriched, "falling", "falling_rank")?;
+    // The current row counts towards `<=`, so each new high ranks 1.0 rather than
+    // climbing from 0; falling and dipping values are what move the rank.
+    assert_eq!(column_values(&enriched, "rising_rank"), vec![1.0; 4]);
+    assert_eq!(
+        column_values(&enriched, "falling_rank"),
+        vec![1.0, 0.5, 1.0 / 3.0, 0.25]
+    );
+
+    let mixed = df! { "x" => &[2.0, 1.0, 2.0, 5.0, 3.0] }?;
+    let ranks = column_values(
+        &with_expanding_percentile_rank(&mixed, "x", "x_rank")?,
+        "x_rank",
+    );
+    let expected = [1.0, 0.5, 1.0, 1.0, 0.8];
+    for (actual, expected) in ranks.iter().zip(expected) {
+        assert_abs_diff_eq!(*actual, expected, epsilon = 1e-12);
+    }
+
+    let dipping = df! { "x" => &[3.0, 1.0, 2.0, 4.0, 1.0] }?;
+    let ranks = column_values(
+        &with_expanding_percentile_rank(&dipping, "x", "x_rank")?,
+        "x_rank",
+    );
+    assert_eq!(ranks, vec![1.0, 0.5, 2.0 / 3.0, 1.0, 0.4]);
+
+    let with_nulls = df! { "x" => &[None, None, Some(2.0), Some(1.0), None, Some(3.0)] }?;
+    let enriched = with_expanding_percentile_rank(&with_nulls, "x", "x_rank")?;
+    let ranks: Vec<Option<f64>> = enriched.column("x_rank")?.f64()?.into_iter().collect();
+    assert_eq!(
+        ranks,
+        vec![None, None, Some(1.0), Some(0.5), None, Some(1.0)]
+    );
+    Ok(())
+}
+
+#[test]
+fn rolling_apply_runs_the_closure_over_each_window() -> anyhow::Result<()> {
+    let frame = df! { "x" => &[1.0, 2.0, 3.0, 4.0, 5.0] }?;
+
+    let enriched = with_rolling_apply(&frame, "x", 3, "x_sum", |window| window.iter().sum())?;
+    assert_eq!(
+        column_values(&enriched, "x_sum"),
+        vec![1.0, 3.0, 6.0, 9.0, 12.0]
+    );
+
+    let lengths = with_rolling_apply(&frame, "x", 3, "len", |window| window.len() as f64)?;
+    assert_eq!(
+        column_values(&lengths, "len"),
+        vec![1.0, 2.0, 3.0, 3.0, 3.0]
+    );
+    Ok(())
+}
+
+#[test]
+fn reconstructed_price_inverts_daily_returns() -> anyhow::Result<()> {
+    let prices = [100.0, 101.0, 102.0, 104.0, 103.0];
+    let frame = df! { "close" => &prices }?;
+
+    let with_returns = with_daily_returns(&frame, "close", "return")?;
+    let rebuilt = with_reconstructed_price(&with_returns, "return", prices[0], "rebuilt")?;
+    for (actual, expected) in column_values(&rebuilt, "rebuilt").iter().zip(prices) {
+        assert_abs_diff_eq!(*actual, expected, epsilon = 1e-9);
+    }
+
+    let gappy = df! { "return" => &[0.1, f64::NAN, -0.5] }?;
+    let rebuilt = with_reconstructed_price(&gappy, "return", 10.0, "price")?;
+    let path = column_values(&rebuilt, "price");
+    assert_abs_diff_eq!(path[1], 11.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(path[2], 5.5, epsilon = 1e-12);
+    Ok(())
+}
+
+#[test]
+fn invalid_inputs_surface_typed_feature_errors() -> anyhow::Result<()> {
+    let frame = df! { "close" => &[1.0, 2.0, 3.0] }?;
+
+    assert!(matches!(
+        with_moving_average(&frame, "close", 0, "ma"),
+        Err(FeatureError::InvalidWindow {
+            window: 0,
+            minimum: 1,
+            ..
+        })
+    ));
+    assert!(matches!(
+        with_z_score(&frame, "close", 1, "z"),
+        Err(FeatureError::InvalidWindow {
+            window: 1,
+            minimum: 2,
+            ..
+        })
+    ));
+    assert!(matches!(
+        z_score_expr("close", 1),
+        Err(FeatureError::InvalidWindow { minimum: 2, .. })
+    ));
+    assert!(matches!(
+        with_ema(&frame, "close", 0, "ema"),
+        Err(FeatureError::InvalidWindow { name: "span", .. })
+    ));
+    assert!(matches!(
+        with_macd(&frame, "close", 26, 12, 9, "trend"),
+        Err(FeatureError::InvalidParameter(_))
+    ));
+    assert!(matches!(
+        with_rolling_std(&frame, "missing", 2, "std"),
+        Err(FeatureError::MissingColumn(ref column)) if column == "missing"
+    ));
+
+    let grouped = df! { "symbol" => &["A", "A", "B"], "close" => &[1.0, 2.0, 3.0] }?;
+    assert!(matches!(
+        with_daily_returns_grouped(&grouped, "ticker", "close", "ret"),
+        Err(FeatureError::MissingColumn(ref column)) if column == "ticker"
+    ));
+    assert!(matches!(
+        with_cross_sectional_rank(&grouped, "missing", "symbol", "rank"),
+        Err(FeatureError::MissingColumn(ref column)) if column == "missing"
+    ));
+    assert!(matches!(
+        with_cross_sectional_z_score(&grouped, "close", "date", "z"),
+        Err(FeatureError::MissingColumn(ref column)) if column == "date"
+    ));
+
+    let empty = df! { "close" => Vec::<f64>::new() }?;
+    assert!(matches!(
+        with_moving_average_strict(&empty, "close", 1, "ma"),
+        Err(FeatureError::EmptyFrame)
+    ));
+
+    let gappy = df! { "close" => &[Some(1.0), None, Some(3.0)] }?;
+    assert!(matches!(
+        with_moving_average_with_policy(&gappy, "close", 2, "ma", NullPolicy::Error),
+        Err(FeatureError::NullValues { nulls: 1, .. })
+    ));
+
+    Ok(())
+}
@@ -1,7 +1,70 @@
-use qliber::logging;
+use qliber::logging::{self, capture_logs, log_event, log_event_at};
+use tracing::Level;
 
 #[test]
 fn logging_initialization_is_idempotent() {
     logging::init_logging().expect("first initialization succeeds");
     logging::init_logging().expect("subsequent initialization succeeds");
 }
+
+#[test]
+fn debug_events_are_suppressed_under_info_filter() {
+    let capture = capture_logs();
+    log_event_at(
+        Level::DEBUG,
+        file!(),
+        "LoggingTest",
+        "debug_event",
+        "tests.logging",
+        line!(),
+        "hidden debug event",
+        None,
+        "none",
+        "GET",
+    );
+    log_event(
+        file!(),
+        "LoggingTest",
+        "info_event",
+        "tests.logging",
+        line!(),
+        "visible info event",
+        None,
+        "none",
+        "GET",
+    );
+
+    let output = capture.contents();
+    assert!(output.contains("visible info event"));
+    assert!(!output.contains("hidden debug event"));
+}
+
+#[test]
+fn captured_event_exposes_schema_fields() {
+    let capture = capture_logs();
+    log_event(
+        "src/example.rs",
+        "LoggingTest",
+        "captured_event",
+        "tests.logging",
+        42,
+        "captured message",
+        Some("boom"),
+        "none",
+        "POST",
+    );
+
+    let events = capture.events();
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event["filename"], "src/example.rs");
+    assert_eq!(event["classname"], "LoggingTest");
+    assert_eq!(event["function"], "captured_event");
+    assert_eq!(event["system_section"], "tests.logging");
+    assert_eq!(event["line_num"], 42);
+    assert_eq!(event["message"], "captured message");
+    assert_eq!(event["error"], "boom");
+    assert_eq!(event["method"], "POST");
+    assert!(event["timestamp"].is_string());
+    assert!(event["derived"].is_string());
+}
@@ -0,0 +1,29 @@
+use qliber::logging::{self, LoggingConfig, capture_logs, log_event};
+
+#[test]
+fn derived_field_is_omitted_when_disabled() -> anyhow::Result<()> {
+    logging::init_logging_with_config(LoggingConfig {
+        include_derived: false,
+    })?;
+
+    let capture = capture_logs();
+    log_event(
+        file!(),
+        "LoggingTest",
+        "derived_disabled",
+        "tests.logging",
+        line!(),
+        "no derived line",
+        None,
+        "none",
+        "GET",
+    );
+
+    let record: serde_json::Value = serde_json::from_str(capture.contents().trim())?;
+    let event = &capture.events()[0];
+    assert_eq!(event["message"], "no derived line");
+    assert!(event.get("derived").is_none());
+    assert!(record["fields"].get("derived").is_none());
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use qliber::logging::{self, log_event};
+use tempfile::tempdir;
+
+#[test]
+fn file_sink_receives_serialized_json() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("qliber.log");
+
+    logging::init_logging_to_file(&path)?;
+    logging::init_logging_to_file(&path)?;
+    log_event(
+        file!(),
+        "LoggingTest",
+        "file_sink",
+        "tests.logging",
+        line!(),
+        "written to the log file",
+        None,
+        "none",
+        "GET",
+    );
+
+    let contents = std::fs::read_to_string(&path)?;
+    let record: serde_json::Value = serde_json::from_str(
+        contents
+            .lines()
+            .find(|line| line.contains("written to the log file"))
+            .expect("event line present"),
+    )?;
+    let event: serde_json::Value = serde_json::from_str(
+        record["fields"]["json"]
+            .as_str()
+            .expect("serialized event field"),
+    )?;
+    assert_eq!(event["function"], "file_sink");
+    assert_eq!(event["message"], "written to the log file");
+    assert!(record["fields"]["derived"].is_string());
+
+    Ok(())
+}
@@ -0,0 +1,1242 @@
+use std::collections::HashMap;
+
+use approx::assert_abs_diff_eq;
+use chrono::{TimeZone, Utc};
+use polars::prelude::*;
+
+use qliber::logging::capture_logs;
+
+use qliber::metrics::{
+    AccumulationMode, AnalysisFrequency, AnnualizationStyle, EvaluationOptions, FrequencyUnit,
+    ICMethod, IndicatorMethod, MetricsAccumulator, MetricsError, PerformanceMetrics, RiskReport,
+    beta_alpha, conditional_value_at_risk, correlation_matrix, covariance_matrix,
+    downside_deviation, drawdown_analysis, drawdown_series, ic_series, indicator_analysis,
+    indicator_analysis_grouped, information_coefficient, martin_ratio, monthly_returns_table,
+    omega_ratio, risk_analysis, risk_analysis_relative, risk_analysis_with_benchmark,
+    rolling_max_drawdown, rolling_metrics, sortino_ratio, tail_ratio, turnover, ulcer_index,
+    validate_series_length, value_at_risk,
+};
+
+fn metric_frame_to_map(frame: &DataFrame) -> HashMap<String, f64> {
+    frame
+        .column("metric")
+        .unwrap()
+        .utf8()
+        .unwrap()
+        .into_iter()
+        .zip(frame.column("risk").unwrap().f64().unwrap())
+        .filter_map(|(metric, value)| match (metric, value) {
+            (Some(metric), Some(value)) => Some((metric.to_string(), value)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn calmar_ratio_is_zero_without_drawdown() {
+    let rising = vec![0.01, 0.02, 0.005];
+
+    for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+        let metrics = PerformanceMetrics::evaluate_with_mode(&rising, 252.0, mode);
+        assert_eq!(metrics.max_drawdown, 0.0);
+        assert_eq!(metrics.calmar_ratio, 0.0);
+    }
+}
+
+#[test]
+fn risk_free_rate_separates_sharpe_from_information_ratio() {
+    let returns = vec![0.01, -0.015, 0.02, -0.005];
+    let risk_free = 0.0001;
+
+    for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+        let baseline = PerformanceMetrics::evaluate_with_mode(&returns, 252.0, mode);
+        let metrics = PerformanceMetrics::evaluate_with_risk_free(&returns, 252.0, mode, risk_free);
+
+        assert!(metrics.sharpe_ratio < metrics.information_ratio);
+        assert_abs_diff_eq!(
+            metrics.information_ratio,
+            baseline.information_ratio,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            metrics.sharpe_ratio,
+            ((metrics.mean_return - risk_free) / metrics.std_dev) * 252.0_f64.sqrt(),
+            epsilon = 1e-9
+        );
+    }
+}
+
+#[test]
+fn drawdown_series_minimum_matches_max_drawdown() {
+    let returns = vec![0.01, -0.015, 0.02, -0.005, -0.03, 0.012];
+
+    for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+        let metrics = PerformanceMetrics::evaluate_with_mode(&returns, 252.0, mode);
+        let series = drawdown_series(&returns, mode);
+
+        assert_eq!(series.len(), returns.len());
+        assert!(series.iter().all(|value| *value <= 0.0));
+        let deepest = series.iter().copied().fold(0.0, f64::min);
+        assert_abs_diff_eq!(deepest, metrics.max_drawdown, epsilon = 1e-12);
+    }
+}
+
+#[test]
+fn drawdown_series_filters_non_finite_returns() {
+    let contaminated = vec![0.01, f64::NAN, -0.015, f64::INFINITY, 0.02];
+    let series = drawdown_series(&contaminated, AccumulationMode::Sum);
+
+    assert_eq!(series.len(), 3);
+    assert_abs_diff_eq!(series[0], 0.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(series[1], -0.015, epsilon = 1e-12);
+    assert_abs_diff_eq!(series[2], 0.0, epsilon = 1e-12);
+}
+
+#[test]
+fn max_drawdown_duration_counts_periods_until_recovery() {
+    let sum_returns = vec![0.5, -0.25, -0.25, 0.5, 0.25];
+    let sum_metrics =
+        PerformanceMetrics::evaluate_with_mode(&sum_returns, 252.0, AccumulationMode::Sum);
+    assert_eq!(sum_metrics.max_drawdown_duration, 2);
+
+    let product_returns = vec![0.5, -0.2, -0.25, 1.0];
+    let product_metrics =
+        PerformanceMetrics::evaluate_with_mode(&product_returns, 252.0, AccumulationMode::Product);
+    assert_eq!(product_metrics.max_drawdown_duration, 2);
+
+    let unrecovered = vec![0.5, -0.25, 0.125, -0.25];
+    let unrecovered_metrics =
+        PerformanceMetrics::evaluate_with_mode(&unrecovered, 252.0, AccumulationMode::Sum);
+    assert_eq!(unrecovered_metrics.max_drawdown_duration, 3);
+
+    let flat = vec![0.0, 0.0, 0.0];
+    for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+        let metrics = PerformanceMetrics::evaluate_with_mode(&flat, 252.0, mode);
+        assert_eq!(metrics.max_drawdown_duration, 0);
+    }
+}
+
+#[test]
+fn value_at_risk_interpolates_between_observations() {
+    let returns = vec![0.03, -0.02, f64::NAN, 0.0, -0.05, 0.01];
+
+    assert_abs_diff_eq!(value_at_risk(&returns, 0.9), -0.038, epsilon = 1e-12);
+    assert_abs_diff_eq!(
+        conditional_value_at_risk(&returns, 0.9),
+        -0.05,
+        epsilon = 1e-12
+    );
+
+    assert_abs_diff_eq!(value_at_risk(&returns, 0.75), -0.02, epsilon = 1e-12);
+    assert_abs_diff_eq!(
+        conditional_value_at_risk(&returns, 0.75),
+        -0.035,
+        epsilon = 1e-12
+    );
+}
+
+#[test]
+fn value_at_risk_handles_empty_and_out_of_range_confidence() {
+    assert_eq!(value_at_risk(&[], 0.95), 0.0);
+    assert_eq!(conditional_value_at_risk(&[f64::NAN], 0.95), 0.0);
+
+    let returns = vec![-0.05, -0.02, 0.0, 0.01, 0.03];
+    assert_abs_diff_eq!(value_at_risk(&returns, 1.5), -0.05, epsilon = 1e-9);
+    assert_abs_diff_eq!(value_at_risk(&returns, -1.0), 0.03, epsilon = 1e-9);
+}
+
+#[test]
+fn frequency_presets_wrap_new() {
+    assert_eq!(AnalysisFrequency::DAILY.periods_per_year(), 238.0);
+    assert_eq!(
+        AnalysisFrequency::DAILY,
+        AnalysisFrequency::new(1, FrequencyUnit::Day)
+    );
+    assert_eq!(AnalysisFrequency::daily(), AnalysisFrequency::DAILY);
+    assert_eq!(
+        AnalysisFrequency::weekly(),
+        AnalysisFrequency::new(1, FrequencyUnit::Week)
+    );
+    assert_eq!(AnalysisFrequency::monthly().periods_per_year(), 12.0);
+    assert_eq!(AnalysisFrequency::QUARTERLY.periods_per_year(), 4.0);
+    assert_eq!(AnalysisFrequency::YEARLY.periods_per_year(), 1.0);
+    assert_eq!(
+        AnalysisFrequency::minutes(5),
+        AnalysisFrequency::new(5, FrequencyUnit::Minute)
+    );
+    assert_eq!(AnalysisFrequency::minutes(0).count(), 1);
+}
+
+#[test]
+fn frequency_unit_scalers_match_qlib() {
+    let expected = [
+        (FrequencyUnit::Minute, 240.0 * 238.0),
+        (FrequencyUnit::Day, 238.0),
+        (FrequencyUnit::Week, 50.0),
+        (FrequencyUnit::Month, 12.0),
+        (FrequencyUnit::Quarter, 4.0),
+        (FrequencyUnit::Year, 1.0),
+    ];
+    for (unit, periods) in expected {
+        assert_eq!(unit.default_periods_per_year(), periods);
+        assert_eq!(AnalysisFrequency::new(1, unit).periods_per_year(), periods);
+    }
+    assert_eq!(FrequencyUnit::MINUTES_PER_DAY, 240.0);
+    assert_eq!(FrequencyUnit::DAYS_PER_YEAR, 238.0);
+    assert_eq!(FrequencyUnit::WEEKS_PER_YEAR, 50.0);
+}
+
+#[test]
+fn quarter_and_year_frequencies_parse_with_count_prefix() -> anyhow::Result<()> {
+    for (input, count, unit) in [
+        ("quarter", 1, FrequencyUnit::Quarter),
+        ("q", 1, FrequencyUnit::Quarter),
+        ("2q", 2, FrequencyUnit::Quarter),
+        ("year", 1, FrequencyUnit::Year),
+        ("y", 1, FrequencyUnit::Year),
+        ("3Year", 3, FrequencyUnit::Year),
+    ] {
+        let frequency: AnalysisFrequency = input.parse()?;
+        assert_eq!(frequency.count(), count, "count for `{input}`");
+        assert_eq!(frequency.unit(), unit, "unit for `{input}`");
+    }
+
+    assert_eq!(
+        AnalysisFrequency::new(1, FrequencyUnit::Year).periods_per_year(),
+        1.0
+    );
+    assert_eq!(
+        AnalysisFrequency::new(1, FrequencyUnit::Quarter).periods_per_year(),
+        4.0
+    );
+    assert_eq!(
+        AnalysisFrequency::new(2, FrequencyUnit::Quarter).periods_per_year(),
+        2.0
+    );
+
+    let error = "2decade"
+        .parse::<AnalysisFrequency>()
+        .expect_err("unknown suffix must error");
+    assert!(matches!(error, MetricsError::UnsupportedFrequency(_)));
+
+    Ok(())
+}
+
+#[test]
+fn custom_scaler_overrides_frequency_table() {
+    let returns = vec![0.01, -0.015, 0.02, -0.005];
+    let crypto = AnalysisFrequency::with_scaler(1, FrequencyUnit::Day, 365.0);
+    let default_day = AnalysisFrequency::new(1, FrequencyUnit::Day);
+
+    assert_eq!(crypto.periods_per_year(), 365.0);
+    assert_eq!(crypto.unit(), FrequencyUnit::Day);
+    assert_eq!(default_day.periods_per_year(), 238.0);
+    assert_eq!(
+        AnalysisFrequency::with_scaler(2, FrequencyUnit::Day, 365.0).periods_per_year(),
+        365.0
+    );
+    assert_eq!(
+        crypto,
+        AnalysisFrequency::with_scaler(1, FrequencyUnit::Day, 365.0)
+    );
+    assert_ne!(crypto, default_day);
+
+    let crypto_metrics =
+        PerformanceMetrics::evaluate_with_frequency(&returns, crypto, AccumulationMode::Sum);
+    let default_metrics =
+        PerformanceMetrics::evaluate_with_frequency(&returns, default_day, AccumulationMode::Sum);
+
+    assert_abs_diff_eq!(
+        crypto_metrics.annualized_return,
+        0.0025 * 365.0,
+        epsilon = 1e-12
+    );
+    assert!(crypto_metrics.annualized_return > default_metrics.annualized_return);
+}
+
+#[test]
+fn indicator_analysis_reports_weight_sum() -> anyhow::Result<()> {
+    let frame = df! {
+        "count" => &[5.0, 10.0, 20.0],
+        "ffr" => &[0.1, 0.5, 0.9],
+        "pa" => &[0.2, 0.8, 0.4],
+        "pos" => &[0.3, 0.6, 0.7],
+        "deal_amount" => &[100.0, -400.0, 50.0],
+        "value" => &[1000.0, 200.0, 800.0],
+    }?;
+
+    let amount = indicator_analysis(&frame, IndicatorMethod::AmountWeighted)?;
+    assert_eq!(amount.width(), 3);
+    let weight_sums: HashMap<&str, f64> = amount
+        .column("indicator")?
+        .utf8()?
+        .into_no_null_iter()
+        .zip(amount.column("weight_sum")?.f64()?.into_no_null_iter())
+        .collect();
+
+    assert_abs_diff_eq!(weight_sums["ffr"], 550.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(weight_sums["pa"], 550.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(weight_sums["pos"], 35.0, epsilon = 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn risk_analysis_relative_is_flat_when_tracking_benchmark() -> anyhow::Result<()> {
+    let returns = vec![0.01, -0.015, 0.02, -0.005];
+
+    let frame = risk_analysis_relative(&returns, &returns, Some(252.0), None, Some("sum"))?;
+    let metrics = metric_frame_to_map(&frame);
+    for (name, value) in &metrics {
+        assert_abs_diff_eq!(*value, 0.0, epsilon = 1e-12);
+        assert!(value.is_finite(), "{name} must be finite");
+    }
+
+    let benchmark = vec![0.005, -0.01, 0.01, 0.0];
+    let active: Vec<f64> = returns.iter().zip(&benchmark).map(|(r, b)| r - b).collect();
+    let relative = risk_analysis_relative(&returns, &benchmark, Some(252.0), None, None)?;
+    let direct = risk_analysis(&active, Some(252.0), None, None)?;
+    assert_eq!(metric_frame_to_map(&relative), metric_frame_to_map(&direct));
+
+    let error = risk_analysis_relative(&returns, &benchmark[..3], Some(252.0), None, None)
+        .expect_err("mismatched lengths must error");
+    assert!(matches!(
+        error,
+        MetricsError::LengthMismatch {
+            returns: 4,
+            benchmark: 3
+        }
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn beta_alpha_recovers_leveraged_benchmark() -> anyhow::Result<()> {
+    let benchmark = vec![0.01, -0.02, 0.015, 0.003, -0.007, f64::NAN];
+    let returns: Vec<f64> = benchmark.iter().map(|value| value * 2.0).collect();
+
+    let (beta, alpha) = beta_alpha(&returns, &benchmark, 252.0)?;
+    assert_abs_diff_eq!(beta, 2.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(alpha, 0.0, epsilon = 1e-12);
+
+    let shifted: Vec<f64> = returns.iter().map(|value| value + 0.001).collect();
+    let (beta, alpha) = beta_alpha(&shifted, &benchmark, 252.0)?;
+    assert_abs_diff_eq!(beta, 2.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(alpha, 0.252, epsilon = 1e-12);
+
+    assert!(matches!(
+        beta_alpha(&returns[..2], &benchmark, 252.0),
+        Err(MetricsError::LengthMismatch { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn rolling_metrics_are_stable_on_stationary_series() -> Result<(), MetricsError> {
+    let pattern = [0.01, -0.005, 0.02, -0.01];
+    let returns: Vec<f64> = pattern.iter().copied().cycle().take(40).collect();
+
+    let rolling = rolling_metrics(&returns, 8, 252.0, AccumulationMode::Sum)?;
+    assert_eq!(rolling.len(), returns.len() - 7);
+
+    let first = rolling[0].sharpe_ratio;
+    assert!(first > 0.0);
+    for metrics in &rolling {
+        assert_abs_diff_eq!(metrics.sharpe_ratio, first, epsilon = 1e-9);
+    }
+
+    assert!(rolling_metrics(&returns[..5], 8, 252.0, AccumulationMode::Sum)?.is_empty());
+    assert!(matches!(
+        rolling_metrics(&returns, 0, 252.0, AccumulationMode::Sum),
+        Err(MetricsError::InvalidWindow(0))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn sortino_ratio_matches_hand_computed_downside_deviation() {
+    let returns = [0.01, -0.015, 0.02, -0.005, f64::NAN];
+
+    // Downside deviation over the four finite returns: sqrt((0.015^2 + 0.005^2) / 4).
+    let downside = 0.000_062_5_f64.sqrt();
+    assert_abs_diff_eq!(downside, 0.007_905_694_150_420_948, epsilon = 1e-15);
+    assert_abs_diff_eq!(
+        sortino_ratio(&returns, 252.0),
+        0.0025 / downside * 252.0_f64.sqrt(),
+        epsilon = 1e-12
+    );
+    assert_abs_diff_eq!(
+        sortino_ratio(&returns, 252.0),
+        5.019_960_159_204_453,
+        epsilon = 1e-9
+    );
+
+    assert_eq!(sortino_ratio(&[0.01, 0.02, 0.0], 252.0), 0.0);
+}
+
+#[test]
+fn omega_ratio_is_balanced_on_symmetric_series() {
+    let returns = vec![-0.02, -0.01, 0.0, 0.01, 0.02, f64::NAN];
+
+    assert_abs_diff_eq!(omega_ratio(&returns, 0.0), 1.0, epsilon = 1e-12);
+    let lower = omega_ratio(&returns, -0.005);
+    let lowest = omega_ratio(&returns, -0.015);
+    assert!(lower > 1.0);
+    assert!(lowest > lower);
+
+    assert_eq!(omega_ratio(&[0.01, 0.02], 0.0), 0.0);
+    assert_eq!(omega_ratio(&[], 0.0), 0.0);
+}
+
+#[test]
+fn risk_report_matches_individual_functions() -> anyhow::Result<()> {
+    let returns = vec![0.01, -0.015, 0.02, -0.005, -0.03, 0.012, 0.004];
+    let benchmark = vec![0.008, -0.01, 0.015, 0.0, -0.02, 0.01, 0.002];
+
+    let report = RiskReport::generate(&returns, Some(&benchmark), 252.0, AccumulationMode::Sum)?;
+    assert_eq!(
+        report.metrics,
+        PerformanceMetrics::evaluate_with_mode(&returns, 252.0, AccumulationMode::Sum)
+    );
+    assert_eq!(report.value_at_risk, value_at_risk(&returns, 0.95));
+    assert_eq!(
+        report.conditional_value_at_risk,
+        conditional_value_at_risk(&returns, 0.95)
+    );
+    assert_eq!(report.sortino_ratio, sortino_ratio(&returns, 252.0));
+    let (beta, alpha) = beta_alpha(&returns, &benchmark, 252.0)?;
+    assert_eq!(report.beta, Some(beta));
+    assert_eq!(report.alpha, Some(alpha));
+
+    let rows = metric_frame_to_map(&report.to_dataframe()?);
+    let base = metric_frame_to_map(&risk_analysis(&returns, Some(252.0), None, Some("sum"))?);
+    for (name, value) in &base {
+        assert_eq!(rows[name], *value, "row {name}");
+    }
+    assert_eq!(rows["sortino"], report.sortino_ratio);
+    assert_eq!(rows["beta"], beta);
+    assert_eq!(rows["calmar"], report.metrics.calmar_ratio);
+
+    let standalone = RiskReport::generate(&returns, None, 252.0, AccumulationMode::Sum)?;
+    assert_eq!(standalone.beta, None);
+    assert!(!metric_frame_to_map(&standalone.to_dataframe()?).contains_key("beta"));
+
+    Ok(())
+}
+
+#[test]
+fn benchmark_separates_information_ratio_from_sharpe() -> anyhow::Result<()> {
+    let returns = vec![0.01, -0.015, 0.02, -0.005, 0.012];
+    let benchmark = vec![0.004, -0.002, 0.006, 0.001, -0.003];
+
+    let absolute = metric_frame_to_map(&risk_analysis(&returns, Some(252.0), None, None)?);
+    assert_eq!(absolute["sharpe"], absolute["information_ratio"]);
+
+    let relative = metric_frame_to_map(&risk_analysis_with_benchmark(
+        &returns,
+        Some(&benchmark),
+        Some(252.0),
+        None,
+        None,
+    )?);
+    assert_eq!(relative["sharpe"], absolute["sharpe"]);
+    assert!((relative["information_ratio"] - relative["sharpe"]).abs() > 1e-3);
+
+    let active: Vec<f64> = returns.iter().zip(&benchmark).map(|(r, b)| r - b).collect();
+    let active_metrics = PerformanceMetrics::evaluate(&active, 252.0);
+    assert_abs_diff_eq!(
+        relative["information_ratio"],
+        active_metrics.information_ratio,
+        epsilon = 1e-12
+    );
+
+    assert!(matches!(
+        risk_analysis_with_benchmark(&returns, Some(&benchmark[..2]), Some(252.0), None, None),
+        Err(MetricsError::LengthMismatch { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn evaluate_with_curve_ends_at_cumulative_return() {
+    let returns = vec![0.01, f64::NAN, -0.015, 0.02, f64::INFINITY, -0.005];
+
+    let (sum_metrics, sum_curve) =
+        PerformanceMetrics::evaluate_with_curve(&returns, 252.0, AccumulationMode::Sum);
+    assert_eq!(sum_curve.len(), 4);
+    assert_abs_diff_eq!(
+        *sum_curve.last().unwrap(),
+        sum_metrics.cumulative_return,
+        epsilon = 1e-12
+    );
+    assert_eq!(
+        sum_metrics,
+        PerformanceMetrics::evaluate_with_mode(&returns, 252.0, AccumulationMode::Sum)
+    );
+
+    let (product_metrics, product_curve) =
+        PerformanceMetrics::evaluate_with_curve(&returns, 252.0, AccumulationMode::Product);
+    assert_eq!(product_curve.len(), 4);
+    assert_abs_diff_eq!(
+        *product_curve.last().unwrap(),
+        1.0 + product_metrics.cumulative_return,
+        epsilon = 1e-12
+    );
+
+    let (empty_metrics, empty_curve) =
+        PerformanceMetrics::evaluate_with_curve(&[f64::NAN], 252.0, AccumulationMode::Product);
+    assert!(empty_curve.is_empty());
+    assert_eq!(empty_metrics.cumulative_return, 0.0);
+}
+
+#[test]
+fn downside_deviation_averages_shortfalls_over_all_returns() {
+    let returns = vec![0.02, -0.01, 0.03, -0.03, f64::NAN, 0.0];
+
+    let expected = ((0.01_f64.powi(2) + 0.03_f64.powi(2)) / 5.0).sqrt() * 252.0_f64.sqrt();
+    assert_abs_diff_eq!(
+        downside_deviation(&returns, 0.0, 252.0),
+        expected,
+        epsilon = 1e-12
+    );
+
+    let raised = ((0.02_f64.powi(2) + 0.04_f64.powi(2) + 0.01_f64.powi(2)) / 5.0).sqrt();
+    assert_abs_diff_eq!(
+        downside_deviation(&returns, 0.01, 1.0),
+        raised,
+        epsilon = 1e-12
+    );
+
+    assert_eq!(downside_deviation(&[0.01, 0.02], 0.0, 252.0), 0.0);
+    assert_eq!(downside_deviation(&[], 0.0, 252.0), 0.0);
+}
+
+#[test]
+fn ulcer_index_reflects_drawdown_depth() {
+    let returns = vec![0.5, -0.25, -0.25, 0.5, 0.25];
+
+    let ulcer = ulcer_index(&returns, AccumulationMode::Sum);
+    let expected = ((0.25_f64.powi(2) + 0.5_f64.powi(2)) / 5.0).sqrt();
+    assert!(ulcer > 0.0);
+    assert_abs_diff_eq!(ulcer, expected, epsilon = 1e-12);
+
+    let metrics = PerformanceMetrics::evaluate_with_mode(&returns, 252.0, AccumulationMode::Sum);
+    assert_abs_diff_eq!(
+        martin_ratio(&returns, 252.0, AccumulationMode::Sum),
+        metrics.annualized_return / expected,
+        epsilon = 1e-9
+    );
+    assert!(ulcer_index(&returns, AccumulationMode::Product) > 0.0);
+
+    let rising = vec![0.01, 0.02, 0.005];
+    for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+        assert_eq!(ulcer_index(&rising, mode), 0.0);
+        assert_eq!(martin_ratio(&rising, 252.0, mode), 0.0);
+    }
+}
+
+#[test]
+fn monthly_returns_table_compounds_within_each_month() -> anyhow::Result<()> {
+    let date = |month, day| chrono::NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+    let frame = DataFrame::new(vec![
+        Series::new(
+            "date",
+            vec![date(2, 1), date(1, 2), date(1, 3), date(2, 2), date(1, 4)],
+        ),
+        Series::new("ret", &[0.05, 0.1, -0.05, -0.02, f64::NAN]),
+    ])?;
+
+    let table = monthly_returns_table(&frame, "date", "ret")?;
+    assert_eq!(table.height(), 2);
+
+    let months: Vec<u32> = table
+        .column("month")?
+        .cast(&DataType::UInt32)?
+        .u32()?
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(months, vec![1, 2]);
+    let years: Vec<i32> = table.column("year")?.i32()?.into_no_null_iter().collect();
+    assert_eq!(years, vec![2024, 2024]);
+
+    let compounded: Vec<f64> = table.column("ret")?.f64()?.into_no_null_iter().collect();
+    assert_abs_diff_eq!(compounded[0], 1.1 * 0.95 - 1.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(compounded[1], 1.05 * 0.98 - 1.0, epsilon = 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn minute_frequency_accepts_custom_session() {
+    let crypto = AnalysisFrequency::minute(1440, 365);
+    assert_eq!(crypto.unit(), FrequencyUnit::Minute);
+    assert_eq!(crypto.count(), 1);
+    assert_eq!(crypto.periods_per_year(), 525_600.0);
+
+    assert_eq!(
+        AnalysisFrequency::new(1, FrequencyUnit::Minute).periods_per_year(),
+        240.0 * 238.0
+    );
+    assert_eq!(
+        AnalysisFrequency::minute(240, 238).periods_per_year(),
+        AnalysisFrequency::new(1, FrequencyUnit::Minute).periods_per_year()
+    );
+}
+
+#[test]
+fn tail_ratio_is_balanced_on_symmetric_series() -> Result<(), MetricsError> {
+    let returns: Vec<f64> = (-50..=50)
+        .map(|step| f64::from(step) / 1000.0)
+        .chain([f64::NAN])
+        .collect();
+
+    assert_abs_diff_eq!(tail_ratio(&returns, 0.05)?, 1.0, epsilon = 1e-9);
+
+    let skewed: Vec<f64> = returns.iter().map(|value| value.max(-0.01)).collect();
+    assert!(tail_ratio(&skewed, 0.05)? > 1.0);
+
+    assert_eq!(tail_ratio(&[0.0, 0.0, 0.01, 0.02, 0.03], 0.25)?, 0.0);
+    assert_eq!(tail_ratio(&[], 0.05)?, 0.0);
+
+    for tail in [0.0, 0.5, 0.7, -0.05, f64::NAN] {
+        assert!(matches!(
+            tail_ratio(&returns, tail),
+            Err(MetricsError::InvalidTail(_))
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn turnover_sums_absolute_weight_changes() -> anyhow::Result<()> {
+    let weights = df! {
+        "date" => &[2, 1, 1, 2, 1, 2],
+        "symbol" => &["AAA", "AAA", "BBB", "BBB", "CCC", "CCC"],
+        "weight" => &[0.3, 0.5, 0.3, 0.5, -0.2, 0.0],
+    }?;
+
+    let frame = turnover(&weights, "date", "symbol", "weight")?;
+    let dates: Vec<i32> = frame.column("date")?.i32()?.into_no_null_iter().collect();
+    assert_eq!(dates, vec![1, 2]);
+
+    let values: Vec<f64> = frame
+        .column("turnover")?
+        .f64()?
+        .into_no_null_iter()
+        .collect();
+    assert_abs_diff_eq!(values[0], 1.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(values[1], 0.2 + 0.2 + 0.2, epsilon = 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn indicator_analysis_grouped_keeps_groups_independent() -> anyhow::Result<()> {
+    let frame = df! {
+        "date" => &["2024-01-02", "2024-01-01", "2024-01-02", "2024-01-01"],
+        "count" => &[1.0, 2.0, 3.0, 2.0],
+        "ffr" => &[0.2, 0.5, 0.6, 0.7],
+        "pa" => &[0.1, 0.4, 0.3, 0.2],
+        "pos" => &[1.0, 0.0, 0.5, 1.0],
+    }?;
+
+    let grouped = indicator_analysis_grouped(&frame, "date", IndicatorMethod::Mean)?;
+    assert_eq!(grouped.height(), 6);
+
+    let dates: Vec<&str> = grouped
+        .column("date")?
+        .utf8()?
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(
+        dates,
+        [
+            "2024-01-01",
+            "2024-01-01",
+            "2024-01-01",
+            "2024-01-02",
+            "2024-01-02",
+            "2024-01-02"
+        ]
+    );
+
+    for (offset, date) in [(0, "2024-01-01"), (3, "2024-01-02")] {
+        let mask = frame.column("date")?.utf8()?.equal(date);
+        let single = indicator_analysis(&frame.filter(&mask)?, IndicatorMethod::Mean)?;
+        let expected: Vec<f64> = single.column("value")?.f64()?.into_no_null_iter().collect();
+        let actual: Vec<f64> = grouped
+            .column("value")?
+            .f64()?
+            .into_no_null_iter()
+            .skip(offset)
+            .take(3)
+            .collect();
+        assert_eq!(actual, expected, "group {date}");
+    }
+
+    let values: Vec<f64> = grouped
+        .column("value")?
+        .f64()?
+        .into_no_null_iter()
+        .collect();
+    assert_abs_diff_eq!(values[0], 0.6, epsilon = 1e-12);
+    assert_abs_diff_eq!(values[3], 0.5, epsilon = 1e-12);
+
+    assert!(matches!(
+        indicator_analysis_grouped(&frame, "missing", IndicatorMethod::Mean),
+        Err(MetricsError::MissingColumn(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn evaluate_from_frame_matches_slice_evaluation() -> anyhow::Result<()> {
+    let returns = vec![0.01, -0.015, 0.02, -0.005, 0.012];
+    let frame = DataFrame::new(vec![Series::new(
+        "ret",
+        &[
+            Some(0.01),
+            None,
+            Some(-0.015),
+            Some(0.02),
+            Some(f64::NAN),
+            Some(-0.005),
+            Some(0.012),
+        ],
+    )])?;
+
+    for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+        assert_eq!(
+            PerformanceMetrics::evaluate_from_frame(&frame, "ret", 252.0, mode)?,
+            PerformanceMetrics::evaluate_with_mode(&returns, 252.0, mode)
+        );
+    }
+
+    let integers = df! { "ret" => &[1_i64, -1, 2] }?;
+    assert_eq!(
+        PerformanceMetrics::evaluate_from_frame(&integers, "ret", 1.0, AccumulationMode::Sum)?,
+        PerformanceMetrics::evaluate_with_mode(&[1.0, -1.0, 2.0], 1.0, AccumulationMode::Sum)
+    );
+
+    assert!(matches!(
+        PerformanceMetrics::evaluate_from_frame(&frame, "missing", 252.0, AccumulationMode::Sum),
+        Err(MetricsError::MissingColumn(name)) if name == "missing"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn validate_series_length_warns_on_missing_days() {
+    let monday = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let friday = Utc.with_ymd_and_hms(2024, 1, 5, 0, 0, 0).unwrap();
+    let next_monday = Utc.with_ymd_and_hms(2024, 1, 8, 0, 0, 0).unwrap();
+    let daily = AnalysisFrequency::DAILY;
+    assert_eq!(daily.expected_periods(monday, friday), 5.0);
+    assert_eq!(daily.expected_periods(monday, next_monday), 6.0);
+    assert_eq!(daily.expected_periods(friday, monday), 0.0);
+    assert_eq!(
+        AnalysisFrequency::minutes(30).expected_periods(monday, friday),
+        40.0
+    );
+    assert_eq!(
+        AnalysisFrequency::WEEKLY.expected_periods(monday, next_monday),
+        2.0
+    );
+    assert_abs_diff_eq!(
+        AnalysisFrequency::with_scaler(1, FrequencyUnit::Day, 365.25)
+            .expected_periods(monday, next_monday),
+        8.0,
+        epsilon = 1e-9
+    );
+
+    let full_week = vec![0.01; 5];
+    let capture = capture_logs();
+    assert!(validate_series_length(&full_week, daily, monday, friday));
+    assert!(!capture.contents().contains("WARN"));
+
+    let missing_day = vec![0.01; 4];
+    assert!(!validate_series_length(&missing_day, daily, monday, friday));
+    let output = capture.contents();
+    let warning = output
+        .lines()
+        .find(|line| line.contains("WARN"))
+        .expect("gap must log a warning");
+    assert!(warning.contains("validate_series_length"));
+}
+
+#[test]
+fn metrics_types_round_trip_through_serde_json() -> anyhow::Result<()> {
+    let returns = vec![0.01, -0.015, 0.02, -0.005, -0.03, 0.012];
+    let metrics =
+        PerformanceMetrics::evaluate_with_mode(&returns, 252.0, AccumulationMode::Product);
+
+    let json = serde_json::to_value(metrics)?;
+    assert_eq!(json["sharpe_ratio"], metrics.sharpe_ratio);
+    assert_eq!(json["max_drawdown_duration"], metrics.max_drawdown_duration);
+    let restored: PerformanceMetrics = serde_json::from_value(json)?;
+    assert_eq!(restored, metrics);
+
+    assert_eq!(
+        serde_json::to_string(&AccumulationMode::Product)?,
+        "\"product\""
+    );
+    assert_eq!(
+        serde_json::to_string(&IndicatorMethod::AmountWeighted)?,
+        "\"amount_weighted\""
+    );
+    for mode in ["sum", "product"] {
+        let parsed: AccumulationMode = serde_json::from_str(&format!("\"{mode}\""))?;
+        assert_eq!(parsed, mode.parse()?);
+    }
+
+    let crypto = AnalysisFrequency::with_scaler(2, FrequencyUnit::Day, 365.0);
+    let json = serde_json::to_value(crypto)?;
+    assert_eq!(
+        json,
+        serde_json::json!({ "count": 2, "unit": "day", "periods_per_year": 365.0 })
+    );
+    assert_eq!(serde_json::from_value::<AnalysisFrequency>(json)?, crypto);
+
+    let normalized: AnalysisFrequency =
+        serde_json::from_str(r#"{ "count": 0, "unit": "minute" }"#)?;
+    assert_eq!(normalized, AnalysisFrequency::new(1, FrequencyUnit::Minute));
+
+    Ok(())
+}
+
+#[test]
+fn accumulation_mode_round_trips_through_display() -> Result<(), MetricsError> {
+    for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+        assert_eq!(mode.to_string().parse::<AccumulationMode>()?, mode);
+    }
+    assert_eq!(AccumulationMode::Product.to_string(), "product");
+    assert_eq!(" SUM ".parse::<AccumulationMode>()?, AccumulationMode::Sum);
+
+    let err = "geometric".parse::<AccumulationMode>().unwrap_err();
+    assert!(matches!(err, MetricsError::InvalidAccumulationMode(ref mode) if mode == "geometric"));
+
+    Ok(())
+}
+
+#[test]
+fn indicator_method_parses_names_and_aliases() -> Result<(), MetricsError> {
+    let cases = [
+        ("mean", IndicatorMethod::Mean),
+        ("amount_weighted", IndicatorMethod::AmountWeighted),
+        ("amount", IndicatorMethod::AmountWeighted),
+        ("value_weighted", IndicatorMethod::ValueWeighted),
+        ("Value", IndicatorMethod::ValueWeighted),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(input.parse::<IndicatorMethod>()?, expected);
+    }
+
+    let err = "median".parse::<IndicatorMethod>().unwrap_err();
+    assert!(matches!(err, MetricsError::InvalidIndicatorMethod(ref method) if method == "median"));
+    assert!(err.to_string().contains("value_weighted"));
+
+    Ok(())
+}
+
+#[test]
+fn performance_metrics_display_is_a_readable_table() {
+    let returns = vec![0.01, -0.015, 0.02, -0.005];
+    let metrics = PerformanceMetrics::evaluate_with_mode(&returns, 252.0, AccumulationMode::Sum);
+    let rendered = metrics.to_string();
+
+    assert_eq!(rendered.lines().count(), 10);
+    assert!(rendered.contains("Sharpe ratio"));
+    assert!(rendered.contains(&format!("{:.4}", metrics.sharpe_ratio)));
+    assert!(rendered.contains("Cumulative return"));
+    assert!(rendered.contains("1.0000%"));
+    assert!(rendered.contains("Max drawdown duration"));
+
+    let widths: Vec<usize> = rendered.lines().map(str::len).collect();
+    assert!(widths.iter().all(|width| *width == widths[0]));
+
+    let extremes = PerformanceMetrics {
+        sharpe_ratio: 0.000_012_34,
+        information_ratio: 0.001_234_567,
+        calmar_ratio: 123_456_789.0,
+        annualized_return: 99.999_996,
+        ..metrics
+    };
+    let rendered = extremes.to_string();
+    let row = |label: &str| {
+        rendered
+            .lines()
+            .find(|line| line.starts_with(label))
+            .map(|line| line[label.len()..].trim().to_string())
+            .unwrap()
+    };
+    assert_eq!(row("Sharpe ratio"), "1.2340e-5");
+    assert_eq!(row("Information ratio"), "0.0012346");
+    assert_eq!(row("Calmar ratio"), "1.2346e8");
+    assert_eq!(row("Annualized return"), "10000%");
+    assert_eq!(row("Mean return"), "0.25000%");
+}
+
+#[test]
+fn spearman_ic_is_one_for_perfect_rank_order() -> anyhow::Result<()> {
+    let predictions = vec![0.1, 0.4, 0.2, 0.9, f64::NAN];
+    let returns = vec![-0.02, 0.03, 0.0, 0.5, 0.01];
+
+    let spearman = information_coefficient(&predictions, &returns, ICMethod::Spearman)?;
+    assert_abs_diff_eq!(spearman, 1.0, epsilon = 1e-12);
+    let pearson = information_coefficient(&predictions, &returns, ICMethod::Pearson)?;
+    assert!(pearson > 0.0 && pearson < 1.0);
+
+    let reversed: Vec<f64> = returns.iter().map(|value| -value).collect();
+    assert_abs_diff_eq!(
+        information_coefficient(&predictions, &reversed, ICMethod::Spearman)?,
+        -1.0,
+        epsilon = 1e-12
+    );
+
+    let tied = information_coefficient(&[1.0, 1.0, 2.0], &[1.0, 2.0, 3.0], ICMethod::Spearman)?;
+    assert_abs_diff_eq!(tied, 0.75_f64.sqrt(), epsilon = 1e-12);
+
+    assert!(matches!(
+        information_coefficient(&predictions[..2], &returns, ICMethod::Pearson),
+        Err(MetricsError::PredictionLengthMismatch {
+            predictions: 2,
+            returns: 5
+        })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn ic_series_computes_each_date_independently() -> anyhow::Result<()> {
+    let frame = df! {
+        "date" => &[2, 1, 2, 1, 1, 2, 3, 3],
+        "score" => &[0.3, 0.1, 0.2, 0.2, 0.3, 0.1, 0.5, f64::NAN],
+        "ret" => &[-0.01, 0.01, 0.0, 0.02, 0.05, 0.02, 0.01, 0.02],
+    }?;
+
+    let series = ic_series(&frame, "date", "score", "ret", ICMethod::Spearman)?;
+    let dates: Vec<i32> = series.column("date")?.i32()?.into_no_null_iter().collect();
+    assert_eq!(dates, vec![1, 2]);
+
+    let ics: Vec<f64> = series.column("ic")?.f64()?.into_no_null_iter().collect();
+    assert_abs_diff_eq!(ics[0], 1.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(ics[1], -1.0, epsilon = 1e-12);
+
+    Ok(())
+}
+
+#[test]
+fn compounded_annualization_applies_to_sum_mode() {
+    let returns = vec![0.01, -0.015, 0.02, -0.005];
+    let simple = PerformanceMetrics::evaluate_with_mode(&returns, 252.0, AccumulationMode::Sum);
+    let options = EvaluationOptions {
+        annualization: AnnualizationStyle::Compounded,
+        ..EvaluationOptions::default()
+    };
+    let compounded =
+        PerformanceMetrics::evaluate_with_options(&returns, 252.0, AccumulationMode::Sum, &options);
+
+    assert_abs_diff_eq!(simple.annualized_return, 0.0025 * 252.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(
+        compounded.annualized_return,
+        1.0025_f64.powf(252.0) - 1.0,
+        epsilon = 1e-12
+    );
+    assert!(compounded.annualized_return > simple.annualized_return);
+    assert_eq!(compounded.sharpe_ratio, simple.sharpe_ratio);
+    assert_eq!(compounded.cumulative_return, simple.cumulative_return);
+    assert_abs_diff_eq!(
+        compounded.calmar_ratio,
+        compounded.annualized_return / compounded.max_drawdown.abs(),
+        epsilon = 1e-12
+    );
+
+    assert_eq!(
+        PerformanceMetrics::evaluate_with_options(
+            &returns,
+            252.0,
+            AccumulationMode::Sum,
+            &EvaluationOptions::default()
+        ),
+        simple
+    );
+}
+
+#[test]
+fn population_ddof_shrinks_std_by_bessel_factor() {
+    let returns = vec![0.01, -0.015, 0.02, -0.005];
+    let sample = PerformanceMetrics::evaluate_with_mode(&returns, 252.0, AccumulationMode::Sum);
+    let options = EvaluationOptions {
+        ddof: 0,
+        ..EvaluationOptions::default()
+    };
+    let population =
+        PerformanceMetrics::evaluate_with_options(&returns, 252.0, AccumulationMode::Sum, &options);
+
+    assert_eq!(EvaluationOptions::default().ddof, 1);
+    assert_abs_diff_eq!(
+        population.std_dev,
+        sample.std_dev * (3.0_f64 / 4.0).sqrt(),
+        epsilon = 1e-12
+    );
+    assert_abs_diff_eq!(population.std_dev, 0.000_181_25_f64.sqrt(), epsilon = 1e-12);
+    assert!(population.sharpe_ratio > sample.sharpe_ratio);
+    assert_eq!(population.mean_return, sample.mean_return);
+}
+
+#[test]
+fn single_return_semantics_are_pinned() {
+    for value in [0.02, -0.03] {
+        for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+            let metrics = PerformanceMetrics::evaluate_with_mode(&[value], 252.0, mode);
+            assert_abs_diff_eq!(metrics.mean_return, value, epsilon = 1e-15);
+            assert_abs_diff_eq!(metrics.cumulative_return, value, epsilon = 1e-15);
+            assert_eq!(metrics.std_dev, 0.0, "{mode:?}");
+            assert_eq!(metrics.annualized_volatility, 0.0, "{mode:?}");
+            assert_eq!(metrics.sharpe_ratio, 0.0, "{mode:?}");
+            assert_eq!(metrics.information_ratio, 0.0, "{mode:?}");
+        }
+
+        let sum = PerformanceMetrics::evaluate_with_mode(&[value], 252.0, AccumulationMode::Sum);
+        assert_abs_diff_eq!(sum.annualized_return, value * 252.0, epsilon = 1e-12);
+        assert_eq!(sum.max_drawdown, value.min(0.0));
+        assert_eq!(sum.max_drawdown_duration, usize::from(value < 0.0));
+
+        let product =
+            PerformanceMetrics::evaluate_with_mode(&[value], 252.0, AccumulationMode::Product);
+        assert_abs_diff_eq!(
+            product.annualized_return,
+            (1.0 + value).powf(252.0) - 1.0,
+            epsilon = 1e-9
+        );
+        assert_eq!(product.max_drawdown, 0.0);
+        assert_eq!(product.max_drawdown_duration, 0);
+        assert_eq!(product.calmar_ratio, 0.0);
+    }
+
+    let loss = PerformanceMetrics::evaluate_with_mode(&[-0.03], 252.0, AccumulationMode::Sum);
+    assert_abs_diff_eq!(loss.calmar_ratio, -252.0, epsilon = 1e-9);
+}
+
+#[test]
+fn drawdown_analysis_minimum_matches_max_drawdown() -> anyhow::Result<()> {
+    let returns = vec![0.01, -0.015, f64::NAN, 0.02, -0.005, -0.03, 0.012];
+
+    for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+        let frame = drawdown_analysis(&returns, mode)?;
+        assert_eq!(
+            frame.get_column_names(),
+            ["period", "equity", "peak", "drawdown"]
+        );
+        assert_eq!(frame.height(), 6);
+
+        let metrics = PerformanceMetrics::evaluate_with_mode(&returns, 252.0, mode);
+        let deepest = frame.column("drawdown")?.f64()?.min().unwrap();
+        assert_abs_diff_eq!(deepest, metrics.max_drawdown, epsilon = 1e-12);
+
+        let drawdowns: Vec<f64> = frame
+            .column("drawdown")?
+            .f64()?
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(drawdowns, drawdown_series(&returns, mode));
+        let equity = frame.column("equity")?.f64()?;
+        let peak = frame.column("peak")?.f64()?;
+        assert!(equity.into_iter().zip(peak).all(|(e, p)| e <= p));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn indicator_analysis_rejects_empty_frame() -> anyhow::Result<()> {
+    let empty: &[f64] = &[];
+    let frame = df! {
+        "count" => empty,
+        "ffr" => empty,
+        "pa" => empty,
+        "pos" => empty,
+    }?;
+
+    for method in [
+        IndicatorMethod::Mean,
+        IndicatorMethod::AmountWeighted,
+        IndicatorMethod::ValueWeighted,
+    ] {
+        assert!(matches!(
+            indicator_analysis(&frame, method),
+            Err(MetricsError::EmptyFrame)
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn rolling_max_drawdown_isolates_the_losing_window() -> Result<(), MetricsError> {
+    let returns = vec![0.01, 0.01, 0.01, -0.05, -0.04, 0.02, 0.01, 0.01, 0.01];
+
+    for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+        let rolling = rolling_max_drawdown(&returns, 3, mode)?;
+        assert_eq!(rolling.len(), returns.len() - 2);
+
+        let (deepest_index, deepest) = rolling
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by(|left, right| left.1.total_cmp(&right.1))
+            .unwrap();
+        assert_eq!(deepest_index, 2, "{mode:?}");
+        assert_abs_diff_eq!(
+            deepest,
+            PerformanceMetrics::evaluate_with_mode(&returns, 252.0, mode).max_drawdown,
+            epsilon = 1e-12
+        );
+        assert_eq!(rolling[0], 0.0);
+        assert_eq!(*rolling.last().unwrap(), 0.0);
+    }
+
+    assert!(rolling_max_drawdown(&returns[..2], 3, AccumulationMode::Sum)?.is_empty());
+    assert!(matches!(
+        rolling_max_drawdown(&returns, 0, AccumulationMode::Product),
+        Err(MetricsError::InvalidWindow(0))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn correlation_matrix_is_symmetric_with_unit_diagonal() -> Result<(), MetricsError> {
+    let frame = df![
+        "a" => [1.0, 2.0, 3.0, 4.0, 5.0],
+        "b" => [2.0, 4.0, f64::NAN, 8.0, 10.0],
+        "c" => [2.0, 1.0, 4.0, 3.0, 5.0],
+    ]?;
+    let matrix = correlation_matrix(&frame, &["a", "b", "c"])?;
+    assert_eq!(matrix.shape(), (3, 3));
+    assert_eq!(matrix.get_column_names(), ["a", "b", "c"]);
+
+    let cell = |row: usize, column: &str| matrix.column(column).unwrap().f64().unwrap().get(row);
+    for (i, name) in ["a", "b", "c"].into_iter().enumerate() {
+        assert_eq!(cell(i, name), Some(1.0));
+        for (j, other) in ["a", "b", "c"].into_iter().enumerate() {
+            assert_eq!(cell(i, other), cell(j, name));
+        }
+    }
+    assert_abs_diff_eq!(cell(0, "b").unwrap(), 1.0, epsilon = 1e-12);
+    assert_abs_diff_eq!(cell(0, "c").unwrap(), 0.8, epsilon = 1e-12);
+    assert_abs_diff_eq!(
+        cell(1, "c").unwrap(),
+        16.0 / 350.0_f64.sqrt(),
+        epsilon = 1e-12
+    );
+
+    let flat = df!["a" => [1.0, 2.0, 3.0], "flat" => [4.0, 4.0, 4.0]]?;
+    let capture = capture_logs();
+    let matrix = correlation_matrix(&flat, &["a", "flat"])?;
+    assert_eq!(matrix.column("a")?.f64()?.get(1), Some(0.0));
+    assert_eq!(matrix.column("flat")?.f64()?.get(1), Some(1.0));
+    assert!(capture.contents().contains("zero variance"));
+
+    Ok(())
+}
+
+#[test]
+fn covariance_matrix_diagonal_is_annualized_variance() -> Result<(), MetricsError> {
+    let frame = df![
+        "a" => [0.01, -0.015, 0.02, -0.005],
+        "b" => [0.002, 0.004, f64::NAN, -0.001],
+        "c" => [-0.01, 0.012, -0.018, 0.006],
+    ]?;
+    let matrix = covariance_matrix(&frame, &["a", "b", "c"], 252.0)?;
+    assert_eq!(matrix.shape(), (3, 3));
+
+    let cell = |row: usize, column: &str| matrix.column(column).unwrap().f64().unwrap().get(row);
+    for (i, name) in ["a", "b", "c"].into_iter().enumerate() {
+        let returns: Vec<f64> = frame.column(name)?.f64()?.into_no_null_iter().collect();
+        let single = PerformanceMetrics::evaluate_with_mode(&returns, 252.0, AccumulationMode::Sum);
+        assert_abs_diff_eq!(
+            cell(i, name).unwrap(),
+            single.std_dev.powi(2) * 252.0,
+            epsilon = 1e-12
+        );
+        for (j, other) in ["a", "b", "c"].into_iter().enumerate() {
+            assert_eq!(cell(i, other), cell(j, name));
+        }
+    }
+    assert_abs_diff_eq!(
+        cell(0, "a").unwrap(),
+        0.000_725 / 3.0 * 252.0,
+        epsilon = 1e-12
+    );
+
+    Ok(())
+}
+
+#[test]
+fn metrics_accumulator_matches_batch_evaluation() {
+    let returns = [0.01, -0.015, f64::NAN, 0.02, -0.005, -0.03, 0.012];
+    for mode in [AccumulationMode::Sum, AccumulationMode::Product] {
+        let mut accumulator = MetricsAccumulator::new(mode);
+        assert_eq!(
+            accumulator.snapshot(252.0),
+            PerformanceMetrics::evaluate_with_mode(&[], 252.0, mode)
+        );
+
+        for (tick, value) in returns.iter().copied().enumerate() {
+            accumulator.push(value);
+            let streamed = accumulator.snapshot(252.0);
+            let batch = PerformanceMetrics::evaluate_with_mode(&returns[..=tick], 252.0, mode);
+            for (left, right) in [
+                (streamed.mean_return, batch.mean_return),
+                (streamed.std_dev, batch.std_dev),
+                (streamed.cumulative_return, batch.cumulative_return),
+                (streamed.annualized_return, batch.annualized_return),
+                (streamed.annualized_volatility, batch.annualized_volatility),
+                (streamed.sharpe_ratio, batch.sharpe_ratio),
+                (streamed.information_ratio, batch.information_ratio),
+                (streamed.max_drawdown, batch.max_drawdown),
+                (streamed.calmar_ratio, batch.calmar_ratio),
+            ] {
+                assert_abs_diff_eq!(left, right, epsilon = 1e-9);
+            }
+            assert_eq!(streamed.max_drawdown_duration, batch.max_drawdown_duration);
+        }
+        assert_eq!(accumulator.count(), 6);
+    }
+}
+
+#[test]
+fn indicator_analysis_flags_all_null_indicator() -> anyhow::Result<()> {
+    let frame = df! {
+        "count" => &[5.0, 10.0, 20.0],
+        "ffr" => &[0.1, 0.5, 0.9],
+        "pa" => &[None::<f64>, None, None],
+        "pos" => &[0.3, 0.6, 0.7],
+    }?;
+    assert!(matches!(
+        indicator_analysis(&frame, IndicatorMethod::Mean),
+        Err(MetricsError::AllNullColumn(ref column)) if column == "pa"
+    ));
+
+    let nan_frame = df! {
+        "count" => &[5.0, 10.0],
+        "ffr" => &[f64::NAN, f64::NAN],
+        "pa" => &[0.2, 0.8],
+        "pos" => &[0.3, 0.6],
+    }?;
+    assert!(matches!(
+        indicator_analysis(&nan_frame, IndicatorMethod::Mean),
+        Err(MetricsError::AllNullColumn(ref column)) if column == "ffr"
+    ));
+
+    Ok(())
+}
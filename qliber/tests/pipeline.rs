@@ -8,7 +8,7 @@ use tempfile::NamedTempFile;
 use polars::prelude::*;
 
 use qliber::dataset::MarketData;
-use qliber::features::{with_daily_returns, with_moving_average, with_z_score};
+use qliber::features::{FeaturePipeline, with_daily_returns, with_moving_average, with_z_score};
 use qliber::logging;
 use qliber::metrics::{
     AccumulationMode, AnalysisFrequency, FrequencyUnit, IndicatorMethod, MetricsError,
@@ -117,6 +117,7 @@ fn performance_metrics_align_with_python_risk_analysis() {
         epsilon = 1e-9
     );
     assert_abs_diff_eq!(sum_mode.max_drawdown, -0.015, epsilon = 1e-12);
+    assert_abs_diff_eq!(sum_mode.calmar_ratio, 42.0, epsilon = 1e-9);
     assert_abs_diff_eq!(
         sum_mode.sharpe_ratio,
         sum_mode.information_ratio,
@@ -156,6 +157,11 @@ fn performance_metrics_align_with_python_risk_analysis() {
         -0.015000000000000013,
         epsilon = 1e-12
     );
+    assert_abs_diff_eq!(
+        product_mode.calmar_ratio,
+        product_mode.annualized_return / product_mode.max_drawdown.abs(),
+        epsilon = 1e-12
+    );
     assert_abs_diff_eq!(
         product_mode.sharpe_ratio,
         product_mode.information_ratio,
@@ -285,6 +291,7 @@ fn risk_analysis_string_api_matches_struct_results() -> anyhow::Result<()> {
         sum_metrics.max_drawdown,
         epsilon = 1e-12
     );
+    assert_abs_diff_eq!(sum_map["calmar"], sum_metrics.calmar_ratio, epsilon = 1e-9);
 
     let default_frame = risk_analysis(&returns, Some(252.0), None, None)?;
     assert_eq!(sum_map, metric_frame_to_map(&default_frame));
@@ -315,6 +322,11 @@ fn risk_analysis_string_api_matches_struct_results() -> anyhow::Result<()> {
         product_metrics.max_drawdown,
         epsilon = 1e-12
     );
+    assert_abs_diff_eq!(
+        product_map["calmar"],
+        product_metrics.calmar_ratio,
+        epsilon = 1e-9
+    );
 
     let freq_frame = risk_analysis(&returns, None, Some("2week"), Some("sum"))?;
     let freq_metrics =
@@ -435,3 +447,27 @@ fn metrics_ignore_non_finite_returns_like_python() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn feature_pipeline_matches_manual_chaining() -> anyhow::Result<()> {
+    let df = df!("close" => &[101.0, 102.0, 104.0, 103.0])?;
+
+    let pipeline = FeaturePipeline::new()
+        .daily_returns("close", "return")
+        .moving_average("close", 2, "ma_2")
+        .z_score("close", 3, "z_close");
+    assert_eq!(pipeline.len(), 3);
+    let piped = pipeline.apply(&df)?;
+
+    let with_returns = with_daily_returns(&df, "close", "return")?;
+    let with_ma = with_moving_average(&with_returns, "close", 2, "ma_2")?;
+    let manual = with_z_score(&with_ma, "close", 3, "z_close")?;
+    assert!(piped.frame_equal(&manual));
+
+    let failing = FeaturePipeline::new()
+        .daily_returns("missing", "return")
+        .moving_average("close", 2, "ma_2");
+    assert!(failing.apply(&df).is_err());
+
+    Ok(())
+}